@@ -17,6 +17,8 @@ use parking_lot::RwLock;
 use sgx_types::*;
 use sgx_urts::SgxEnclave;
 
+mod ias;
+
 // =============================================================================
 // Global State
 // =============================================================================
@@ -46,6 +48,7 @@ pub enum SgxBridgeStatus {
     ErrorKeyNotFound = 10,
     ErrorBufferTooSmall = 11,
     ErrorNotSupported = 12,
+    ErrorRollbackDetected = 13,
     ErrorUnknown = 255,
 }
 
@@ -72,6 +75,26 @@ extern "C" {
         retval: *mut sgx_status_t,
         enclave_id_out: *mut u8,
         enclave_id_len: usize,
+        enclave_id_in: *const u8,
+        enclave_id_in_len: usize,
+        sealed_keystore: *const u8,
+        sealed_keystore_len: usize,
+    ) -> sgx_status_t;
+
+    fn ecall_seal_keystore(
+        eid: sgx_enclave_id_t,
+        retval: *mut sgx_status_t,
+        policy: u8,
+        sealed_out: *mut u8,
+        sealed_buf_len: usize,
+        sealed_len_out: *mut usize,
+    ) -> sgx_status_t;
+
+    fn ecall_unseal_keystore(
+        eid: sgx_enclave_id_t,
+        retval: *mut sgx_status_t,
+        sealed: *const u8,
+        sealed_len: usize,
     ) -> sgx_status_t;
 
     fn ecall_seal_data(
@@ -81,6 +104,12 @@ extern "C" {
         plaintext_len: usize,
         additional_data: *const u8,
         additional_len: usize,
+        key_policy: u16,
+        attribute_mask_flags: u64,
+        attribute_mask_xfrm: u64,
+        misc_mask: u32,
+        counter_uuid: *const u8,
+        counter_value: u32,
         sealed_out: *mut u8,
         sealed_buf_len: usize,
         sealed_len_out: *mut usize,
@@ -91,11 +120,48 @@ extern "C" {
         retval: *mut sgx_status_t,
         sealed: *const u8,
         sealed_len: usize,
+        check_rollback: c_int,
+        rollback_detected_out: *mut c_int,
         plaintext_out: *mut u8,
         plaintext_buf_len: usize,
         plaintext_len_out: *mut usize,
     ) -> sgx_status_t;
 
+    fn ecall_calc_sealed_size(
+        eid: sgx_enclave_id_t,
+        retval: *mut sgx_status_t,
+        plaintext_len: usize,
+        aad_len: usize,
+        sealed_size_out: *mut usize,
+    ) -> sgx_status_t;
+
+    fn ecall_create_monotonic_counter(
+        eid: sgx_enclave_id_t,
+        retval: *mut sgx_status_t,
+        counter_uuid_out: *mut u8,
+        counter_value_out: *mut u32,
+    ) -> sgx_status_t;
+
+    fn ecall_read_monotonic_counter(
+        eid: sgx_enclave_id_t,
+        retval: *mut sgx_status_t,
+        counter_uuid: *const u8,
+        counter_value_out: *mut u32,
+    ) -> sgx_status_t;
+
+    fn ecall_increment_monotonic_counter(
+        eid: sgx_enclave_id_t,
+        retval: *mut sgx_status_t,
+        counter_uuid: *const u8,
+        counter_value_out: *mut u32,
+    ) -> sgx_status_t;
+
+    fn ecall_destroy_monotonic_counter(
+        eid: sgx_enclave_id_t,
+        retval: *mut sgx_status_t,
+        counter_uuid: *const u8,
+    ) -> sgx_status_t;
+
     fn ecall_generate_report(
         eid: sgx_enclave_id_t,
         retval: *mut sgx_status_t,
@@ -110,8 +176,21 @@ extern "C" {
         retval: *mut sgx_status_t,
         key_id: *const u8,
         key_id_len: usize,
+        format: u8,
         public_key_out: *mut u8,
-        public_key_len: usize,
+        public_key_buf_len: usize,
+        public_key_len_out: *mut usize,
+    ) -> sgx_status_t;
+
+    fn ecall_export_public_key(
+        eid: sgx_enclave_id_t,
+        retval: *mut sgx_status_t,
+        key_id: *const u8,
+        key_id_len: usize,
+        format: u8,
+        public_key_out: *mut u8,
+        public_key_buf_len: usize,
+        public_key_len_out: *mut usize,
     ) -> sgx_status_t;
 
     fn ecall_ecdsa_sign(
@@ -125,6 +204,61 @@ extern "C" {
         signature_len: usize,
     ) -> sgx_status_t;
 
+    fn ecall_ecdsa_verify(
+        eid: sgx_enclave_id_t,
+        retval: *mut sgx_status_t,
+        public_key: *const u8,
+        public_key_len: usize,
+        data: *const u8,
+        data_len: usize,
+        signature: *const u8,
+        signature_len: usize,
+        valid_out: *mut i32,
+    ) -> sgx_status_t;
+
+    fn ecall_generate_secp256k1_keypair(
+        eid: sgx_enclave_id_t,
+        retval: *mut sgx_status_t,
+        key_id: *const u8,
+        key_id_len: usize,
+        public_key_out: *mut u8,
+        public_key_len: usize,
+    ) -> sgx_status_t;
+
+    fn ecall_secp256k1_sign(
+        eid: sgx_enclave_id_t,
+        retval: *mut sgx_status_t,
+        key_id: *const u8,
+        key_id_len: usize,
+        data: *const u8,
+        data_len: usize,
+        signature_out: *mut u8,
+        signature_len: usize,
+    ) -> sgx_status_t;
+
+    fn ecall_secp256k1_verify(
+        eid: sgx_enclave_id_t,
+        retval: *mut sgx_status_t,
+        public_key: *const u8,
+        public_key_len: usize,
+        data: *const u8,
+        data_len: usize,
+        signature: *const u8,
+        signature_len: usize,
+        valid_out: *mut i32,
+    ) -> sgx_status_t;
+
+    fn ecall_ecdh(
+        eid: sgx_enclave_id_t,
+        retval: *mut sgx_status_t,
+        key_id: *const u8,
+        key_id_len: usize,
+        peer_public_key: *const u8,
+        peer_public_key_len: usize,
+        shared_out: *mut u8,
+        shared_len: usize,
+    ) -> sgx_status_t;
+
     fn ecall_sha256(
         eid: sgx_enclave_id_t,
         retval: *mut sgx_status_t,
@@ -139,8 +273,9 @@ extern "C" {
         retval: *mut sgx_status_t,
         key: *const u8,
         key_len: usize,
-        iv: *const u8,
+        iv: *mut u8,
         iv_len: usize,
+        generate_iv: i32,
         plaintext: *const u8,
         plaintext_len: usize,
         aad: *const u8,
@@ -179,6 +314,177 @@ extern "C" {
         eid: sgx_enclave_id_t,
         retval: *mut sgx_status_t,
     ) -> sgx_status_t;
+
+    fn ecall_random_bytes(
+        eid: sgx_enclave_id_t,
+        retval: *mut sgx_status_t,
+        buffer_out: *mut u8,
+        length: usize,
+    ) -> sgx_status_t;
+
+    fn ecall_get_target_info(
+        eid: sgx_enclave_id_t,
+        retval: *mut sgx_status_t,
+        target_info_out: *mut sgx_target_info_t,
+    ) -> sgx_status_t;
+
+    fn ecall_verify_report(
+        eid: sgx_enclave_id_t,
+        retval: *mut sgx_status_t,
+        report: *const sgx_report_t,
+        valid_out: *mut i32,
+    ) -> sgx_status_t;
+
+    fn ecall_session_init(
+        eid: sgx_enclave_id_t,
+        retval: *mut sgx_status_t,
+        session_id: *const u8,
+        session_id_len: usize,
+        peer_target_info: *const sgx_target_info_t,
+        public_key_out: *mut u8,
+        public_key_len: usize,
+        report_out: *mut sgx_report_t,
+    ) -> sgx_status_t;
+
+    fn ecall_session_complete(
+        eid: sgx_enclave_id_t,
+        retval: *mut sgx_status_t,
+        session_id: *const u8,
+        session_id_len: usize,
+        peer_public_key: *const u8,
+        peer_public_key_len: usize,
+        peer_report: *const sgx_report_t,
+        expected_mr_enclave: *const u8,
+        expected_mr_signer: *const u8,
+        key_id: *const u8,
+        key_id_len: usize,
+    ) -> sgx_status_t;
+
+    fn ecall_session_encrypt(
+        eid: sgx_enclave_id_t,
+        retval: *mut sgx_status_t,
+        key_id: *const u8,
+        key_id_len: usize,
+        plaintext: *const u8,
+        plaintext_len: usize,
+        aad: *const u8,
+        aad_len: usize,
+        ciphertext_out: *mut u8,
+        ciphertext_len: usize,
+        nonce_out: *mut u8,
+        nonce_len: usize,
+        tag_out: *mut u8,
+        tag_len: usize,
+    ) -> sgx_status_t;
+
+    fn ecall_session_decrypt(
+        eid: sgx_enclave_id_t,
+        retval: *mut sgx_status_t,
+        key_id: *const u8,
+        key_id_len: usize,
+        ciphertext: *const u8,
+        ciphertext_len: usize,
+        aad: *const u8,
+        aad_len: usize,
+        nonce: *const u8,
+        nonce_len: usize,
+        tag: *const u8,
+        tag_len: usize,
+        plaintext_out: *mut u8,
+        plaintext_buf_len: usize,
+    ) -> sgx_status_t;
+
+    fn ecall_secure_handshake_init(
+        eid: sgx_enclave_id_t,
+        retval: *mut sgx_status_t,
+        session_id: *const u8,
+        session_id_len: usize,
+        target_info: *const sgx_target_info_t,
+        public_key_out: *mut u8,
+        public_key_len: usize,
+        report_out: *mut sgx_report_t,
+    ) -> sgx_status_t;
+
+    fn ecall_secure_handshake_finish(
+        eid: sgx_enclave_id_t,
+        retval: *mut sgx_status_t,
+        session_id: *const u8,
+        session_id_len: usize,
+        peer_public_key: *const u8,
+        peer_public_key_len: usize,
+        auth_string_out: *mut u8,
+        auth_string_len: usize,
+    ) -> sgx_status_t;
+
+    fn ecall_secure_session_encrypt(
+        eid: sgx_enclave_id_t,
+        retval: *mut sgx_status_t,
+        session_id: *const u8,
+        session_id_len: usize,
+        plaintext: *const u8,
+        plaintext_len: usize,
+        aad: *const u8,
+        aad_len: usize,
+        ciphertext_out: *mut u8,
+        ciphertext_len: usize,
+        nonce_out: *mut u8,
+        nonce_len: usize,
+        tag_out: *mut u8,
+        tag_len: usize,
+    ) -> sgx_status_t;
+
+    fn ecall_secure_session_decrypt(
+        eid: sgx_enclave_id_t,
+        retval: *mut sgx_status_t,
+        session_id: *const u8,
+        session_id_len: usize,
+        ciphertext: *const u8,
+        ciphertext_len: usize,
+        aad: *const u8,
+        aad_len: usize,
+        nonce: *const u8,
+        nonce_len: usize,
+        tag: *const u8,
+        tag_len: usize,
+        plaintext_out: *mut u8,
+        plaintext_buf_len: usize,
+    ) -> sgx_status_t;
+}
+
+// =============================================================================
+// DCAP Quote Provider Library (untrusted): Quoting Enclave and Quote
+// Verification Library entry points, from `libsgx_dcap_ql.so` /
+// `libsgx_dcap_quoteverify.so`. These aren't ECALLs - the QE is a separate
+// enclave the quote library talks to directly - so they take no `eid`.
+// `sgx_quote3_error_t`, `sgx_ql_qve_collateral_t`, `sgx_ql_qv_result_t`, and
+// `sgx_ql_qe_report_info_t` come from `sgx_types`' DCAP bindings, matching
+// this crate's existing `sgx_types::*` glob import.
+// =============================================================================
+
+extern "C" {
+    fn sgx_qe_get_target_info(p_qe_target_info: *mut sgx_target_info_t) -> sgx_quote3_error_t;
+
+    fn sgx_qe_get_quote_size(p_quote_size: *mut u32) -> sgx_quote3_error_t;
+
+    fn sgx_qe_get_quote(
+        p_app_report: *const sgx_report_t,
+        quote_size: u32,
+        p_quote: *mut u8,
+    ) -> sgx_quote3_error_t;
+
+    fn sgx_qv_get_quote_supplemental_data_size(p_data_size: *mut u32) -> sgx_quote3_error_t;
+
+    fn sgx_qv_verify_quote(
+        p_quote: *const u8,
+        quote_size: u32,
+        p_quote_collateral: *const sgx_ql_qve_collateral_t,
+        expiration_check_date: i64,
+        p_collateral_expiration_status: *mut u32,
+        p_quote_verification_result: *mut sgx_ql_qv_result_t,
+        p_qve_report_info: *mut sgx_ql_qe_report_info_t,
+        supplemental_data_size: u32,
+        p_supplemental_data: *mut u8,
+    ) -> sgx_quote3_error_t;
 }
 
 // =============================================================================
@@ -193,20 +499,53 @@ fn get_enclave_id() -> Result<sgx_enclave_id_t, SgxBridgeStatus> {
     }
 }
 
+/// Sanity-checks a `(ptr, len)` pair handed to us by the CGO caller before it
+/// is passed across the ECALL boundary: rejects a null pointer paired with a
+/// non-zero length, and rejects a pair whose `ptr + len` would wrap the
+/// address space. This is the untrusted side's half of the same defense the
+/// enclave's own `marshal` module applies on the trusted side - the enclave
+/// still re-validates and copies everything itself, but catching an obviously
+/// malformed pointer here avoids handing garbage to the SGX SDK's own
+/// marshalling and getting back a less diagnosable error.
+fn check_buf(ptr: *const u8, len: usize) -> Result<(), SgxBridgeStatus> {
+    if ptr.is_null() && len > 0 {
+        return Err(SgxBridgeStatus::ErrorInvalidParameter);
+    }
+    if (ptr as usize).checked_add(len).is_none() {
+        return Err(SgxBridgeStatus::ErrorInvalidParameter);
+    }
+    Ok(())
+}
+
 // =============================================================================
 // C API Implementation
 // =============================================================================
 
 /// Initialize the SGX enclave.
+///
+/// To restore a prior enclave identity and its sealed key vault across a
+/// restart, pass back a previously-returned `enclave_id_out` as
+/// `enclave_id_in` (32 bytes, or null for a fresh random identity) and the
+/// blob from [`sgx_bridge_seal_keystore`] as `sealed_keystore` (or null to
+/// start with an empty key vault).
 #[no_mangle]
 pub extern "C" fn sgx_bridge_init(
     enclave_path: *const c_char,
     debug: c_int,
     enclave_id_out: *mut u8,
+    enclave_id_in: *const u8,
+    enclave_id_in_len: usize,
+    sealed_keystore: *const u8,
+    sealed_keystore_len: usize,
 ) -> SgxBridgeStatus {
     if enclave_path.is_null() || enclave_id_out.is_null() {
         return SgxBridgeStatus::ErrorInvalidParameter;
     }
+    if let Err(e) = check_buf(enclave_id_in, enclave_id_in_len)
+        .and_then(|_| check_buf(sealed_keystore, sealed_keystore_len))
+    {
+        return e;
+    }
 
     let path = match unsafe { CStr::from_ptr(enclave_path) }.to_str() {
         Ok(s) => s,
@@ -252,7 +591,16 @@ pub extern "C" fn sgx_bridge_init(
     // Initialize enclave and get ID
     let mut retval = sgx_status_t::SGX_SUCCESS;
     let status = unsafe {
-        ecall_initialize(eid, &mut retval, enclave_id_out, 32)
+        ecall_initialize(
+            eid,
+            &mut retval,
+            enclave_id_out,
+            32,
+            enclave_id_in,
+            enclave_id_in_len,
+            sealed_keystore,
+            sealed_keystore_len,
+        )
     };
 
     if status != sgx_status_t::SGX_SUCCESS {
@@ -265,6 +613,70 @@ pub extern "C" fn sgx_bridge_init(
     SgxBridgeStatus::Success
 }
 
+/// Seals the enclave's entire key vault into one blob the host can persist
+/// to disk and pass to a later [`sgx_bridge_init`] (or
+/// [`sgx_bridge_unseal_keystore`]) to restore every generated key.
+#[no_mangle]
+pub extern "C" fn sgx_bridge_seal_keystore(
+    policy: u8,
+    sealed_out: *mut u8,
+    sealed_buf_len: usize,
+    sealed_len_out: *mut usize,
+) -> SgxBridgeStatus {
+    if sealed_out.is_null() || sealed_len_out.is_null() {
+        return SgxBridgeStatus::ErrorInvalidParameter;
+    }
+    if let Err(e) = check_buf(sealed_out as *const u8, sealed_buf_len) {
+        return e;
+    }
+
+    let eid = match get_enclave_id() {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    let mut retval = sgx_status_t::SGX_SUCCESS;
+    let status = unsafe {
+        ecall_seal_keystore(eid, &mut retval, policy, sealed_out, sealed_buf_len, sealed_len_out)
+    };
+
+    if status != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::from(status);
+    }
+    if retval != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::ErrorSealFailed;
+    }
+    SgxBridgeStatus::Success
+}
+
+/// Unseals a blob produced by [`sgx_bridge_seal_keystore`] and replaces the
+/// running enclave's key vault with its contents.
+#[no_mangle]
+pub extern "C" fn sgx_bridge_unseal_keystore(sealed: *const u8, sealed_len: usize) -> SgxBridgeStatus {
+    if sealed.is_null() {
+        return SgxBridgeStatus::ErrorInvalidParameter;
+    }
+    if let Err(e) = check_buf(sealed, sealed_len) {
+        return e;
+    }
+
+    let eid = match get_enclave_id() {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    let mut retval = sgx_status_t::SGX_SUCCESS;
+    let status = unsafe { ecall_unseal_keystore(eid, &mut retval, sealed, sealed_len) };
+
+    if status != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::from(status);
+    }
+    if retval != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::ErrorUnsealFailed;
+    }
+    SgxBridgeStatus::Success
+}
+
 /// Destroy the SGX enclave.
 #[no_mangle]
 pub extern "C" fn sgx_bridge_destroy() -> SgxBridgeStatus {
@@ -299,12 +711,31 @@ pub extern "C" fn sgx_bridge_is_hardware_mode() -> c_int {
 }
 
 /// Seal data.
+///
+/// `key_policy` is a raw SGX key-request policy bitmask - MRENCLAVE
+/// (`0x0001`), MRSIGNER (`0x0002`), or the KSS bits NOISVPRODID
+/// (`0x0004`) / CONFIGID (`0x0008`) / ISVFAMILYID (`0x0010`) /
+/// ISVEXTPRODID (`0x0020`), freely combinable; `0` defaults to plain
+/// MRSIGNER. `attribute_mask_flags`/`attribute_mask_xfrm` and `misc_mask`
+/// select which attribute/misc-select bits must match between sealing and
+/// unsealing; pass all-zero for the SDK's own defaults (every bit must
+/// match). Pass a non-null 16-byte `counter_uuid` (as returned by
+/// [`sgx_bridge_create_monotonic_counter`]) together with its current
+/// `counter_value` to bind the blob for anti-rollback checking in
+/// [`sgx_bridge_unseal_data`]; pass null to seal without rollback
+/// protection.
 #[no_mangle]
 pub extern "C" fn sgx_bridge_seal_data(
     plaintext: *const u8,
     plaintext_len: usize,
     additional_data: *const u8,
     additional_len: usize,
+    key_policy: u16,
+    attribute_mask_flags: u64,
+    attribute_mask_xfrm: u64,
+    misc_mask: u32,
+    counter_uuid: *const u8,
+    counter_value: u32,
     sealed_out: *mut u8,
     sealed_buf_len: usize,
     sealed_len_out: *mut usize,
@@ -312,6 +743,18 @@ pub extern "C" fn sgx_bridge_seal_data(
     if plaintext.is_null() || sealed_out.is_null() || sealed_len_out.is_null() {
         return SgxBridgeStatus::ErrorInvalidParameter;
     }
+    if let Err(e) = check_buf(plaintext, plaintext_len)
+        .and_then(|_| check_buf(additional_data, additional_len))
+        .and_then(|_| check_buf(sealed_out as *const u8, sealed_buf_len))
+        .and_then(|_| check_buf(counter_uuid, if counter_uuid.is_null() { 0 } else { 16 }))
+    {
+        return e;
+    }
+    // Reject a caller-supplied buffer too small for the sealed output before
+    // letting the enclave discover that the hard way.
+    if sealed_buf_len < sgx_bridge_calc_sealed_size(plaintext_len, additional_len) {
+        return SgxBridgeStatus::ErrorBufferTooSmall;
+    }
 
     let eid = match get_enclave_id() {
         Ok(id) => id,
@@ -327,6 +770,12 @@ pub extern "C" fn sgx_bridge_seal_data(
             plaintext_len,
             additional_data,
             additional_len,
+            key_policy,
+            attribute_mask_flags,
+            attribute_mask_xfrm,
+            misc_mask,
+            counter_uuid,
+            counter_value,
             sealed_out,
             sealed_buf_len,
             sealed_len_out,
@@ -344,10 +793,18 @@ pub extern "C" fn sgx_bridge_seal_data(
 }
 
 /// Unseal data.
+///
+/// Pass `check_rollback != 0` for a blob that was sealed with a monotonic
+/// counter binding; `rollback_detected_out` (if non-null) is then set to
+/// `1` and [`SgxBridgeStatus::ErrorRollbackDetected`] is returned if the
+/// blob is a stale copy whose bound counter value is behind the counter's
+/// current value.
 #[no_mangle]
 pub extern "C" fn sgx_bridge_unseal_data(
     sealed: *const u8,
     sealed_len: usize,
+    check_rollback: c_int,
+    rollback_detected_out: *mut c_int,
     plaintext_out: *mut u8,
     plaintext_buf_len: usize,
     plaintext_len_out: *mut usize,
@@ -355,6 +812,11 @@ pub extern "C" fn sgx_bridge_unseal_data(
     if sealed.is_null() || plaintext_out.is_null() || plaintext_len_out.is_null() {
         return SgxBridgeStatus::ErrorInvalidParameter;
     }
+    if let Err(e) = check_buf(sealed, sealed_len)
+        .and_then(|_| check_buf(plaintext_out as *const u8, plaintext_buf_len))
+    {
+        return e;
+    }
 
     let eid = match get_enclave_id() {
         Ok(id) => id,
@@ -362,21 +824,30 @@ pub extern "C" fn sgx_bridge_unseal_data(
     };
 
     let mut retval = sgx_status_t::SGX_SUCCESS;
+    let mut rollback_detected: c_int = 0;
     let status = unsafe {
         ecall_unseal_data(
             eid,
             &mut retval,
             sealed,
             sealed_len,
+            check_rollback,
+            &mut rollback_detected,
             plaintext_out,
             plaintext_buf_len,
             plaintext_len_out,
         )
     };
+    if !rollback_detected_out.is_null() {
+        unsafe { *rollback_detected_out = rollback_detected; }
+    }
 
     if status != sgx_status_t::SGX_SUCCESS {
         return SgxBridgeStatus::from(status);
     }
+    if rollback_detected != 0 {
+        return SgxBridgeStatus::ErrorRollbackDetected;
+    }
     if retval != sgx_status_t::SGX_SUCCESS {
         return SgxBridgeStatus::ErrorUnsealFailed;
     }
@@ -384,25 +855,1223 @@ pub extern "C" fn sgx_bridge_unseal_data(
     SgxBridgeStatus::Success
 }
 
-/// Calculate sealed data size.
+/// Calculate the exact sealed-blob size the SDK will produce for the given
+/// plaintext/AAD lengths, by asking the enclave (which is the only place
+/// `sgx_calc_sealed_data_size` can be called) rather than guessing at a
+/// fixed overhead. Returns `0` if no enclave is loaded.
 #[no_mangle]
 pub extern "C" fn sgx_bridge_calc_sealed_size(
     plaintext_len: usize,
     additional_len: usize,
 ) -> usize {
-    // SGX sealed data overhead: ~560 bytes for metadata + MAC
-    const SEALED_OVERHEAD: usize = 560;
-    plaintext_len + additional_len + SEALED_OVERHEAD
+    let eid = match get_enclave_id() {
+        Ok(id) => id,
+        Err(_) => return 0,
+    };
+
+    let mut retval = sgx_status_t::SGX_SUCCESS;
+    let mut sealed_size: usize = 0;
+    let status = unsafe {
+        ecall_calc_sealed_size(eid, &mut retval, plaintext_len, additional_len, &mut sealed_size)
+    };
+
+    if status != sgx_status_t::SGX_SUCCESS || retval != sgx_status_t::SGX_SUCCESS {
+        return 0;
+    }
+    sealed_size
 }
 
-/// Generate attestation.
+/// Creates a new SGX monotonic counter (initial value `0`) for binding
+/// sealed blobs against rollback, writing its 16-byte UUID to
+/// `counter_uuid_out`.
 #[no_mangle]
-pub extern "C" fn sgx_bridge_generate_attestation(
+pub extern "C" fn sgx_bridge_create_monotonic_counter(
+    counter_uuid_out: *mut u8,
+    counter_value_out: *mut u32,
+) -> SgxBridgeStatus {
+    if counter_value_out.is_null() {
+        return SgxBridgeStatus::ErrorInvalidParameter;
+    }
+    if let Err(e) = check_buf(counter_uuid_out as *const u8, 16) {
+        return e;
+    }
+
+    let eid = match get_enclave_id() {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    let mut retval = sgx_status_t::SGX_SUCCESS;
+    let status =
+        unsafe { ecall_create_monotonic_counter(eid, &mut retval, counter_uuid_out, counter_value_out) };
+
+    if status != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::from(status);
+    }
+    if retval != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::ErrorUnknown;
+    }
+    SgxBridgeStatus::Success
+}
+
+/// Reads the current value of a monotonic counter without incrementing it.
+#[no_mangle]
+pub extern "C" fn sgx_bridge_read_monotonic_counter(
+    counter_uuid: *const u8,
+    counter_value_out: *mut u32,
+) -> SgxBridgeStatus {
+    if counter_value_out.is_null() {
+        return SgxBridgeStatus::ErrorInvalidParameter;
+    }
+    if let Err(e) = check_buf(counter_uuid, 16) {
+        return e;
+    }
+
+    let eid = match get_enclave_id() {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    let mut retval = sgx_status_t::SGX_SUCCESS;
+    let status =
+        unsafe { ecall_read_monotonic_counter(eid, &mut retval, counter_uuid, counter_value_out) };
+
+    if status != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::from(status);
+    }
+    if retval != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::ErrorUnknown;
+    }
+    SgxBridgeStatus::Success
+}
+
+/// Increments a monotonic counter by one and returns its new value - call
+/// this each time the data bound to it is re-sealed, so older copies become
+/// detectably stale.
+#[no_mangle]
+pub extern "C" fn sgx_bridge_increment_monotonic_counter(
+    counter_uuid: *const u8,
+    counter_value_out: *mut u32,
+) -> SgxBridgeStatus {
+    if counter_value_out.is_null() {
+        return SgxBridgeStatus::ErrorInvalidParameter;
+    }
+    if let Err(e) = check_buf(counter_uuid, 16) {
+        return e;
+    }
+
+    let eid = match get_enclave_id() {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    let mut retval = sgx_status_t::SGX_SUCCESS;
+    let status =
+        unsafe { ecall_increment_monotonic_counter(eid, &mut retval, counter_uuid, counter_value_out) };
+
+    if status != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::from(status);
+    }
+    if retval != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::ErrorUnknown;
+    }
+    SgxBridgeStatus::Success
+}
+
+/// Destroys a monotonic counter, releasing its slot in trusted storage.
+#[no_mangle]
+pub extern "C" fn sgx_bridge_destroy_monotonic_counter(counter_uuid: *const u8) -> SgxBridgeStatus {
+    if let Err(e) = check_buf(counter_uuid, 16) {
+        return e;
+    }
+
+    let eid = match get_enclave_id() {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    let mut retval = sgx_status_t::SGX_SUCCESS;
+    let status = unsafe { ecall_destroy_monotonic_counter(eid, &mut retval, counter_uuid) };
+
+    if status != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::from(status);
+    }
+    if retval != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::ErrorUnknown;
+    }
+    SgxBridgeStatus::Success
+}
+
+/// Generate attestation.
+#[no_mangle]
+pub extern "C" fn sgx_bridge_generate_attestation(
     report_data: *const u8,
     report_data_len: usize,
     attestation_out: *mut SgxBridgeAttestation,
 ) -> SgxBridgeStatus {
-    if attestation_out.is_null() {
+    if attestation_out.is_null() {
+        return SgxBridgeStatus::ErrorInvalidParameter;
+    }
+    if let Err(e) = check_buf(report_data, report_data_len) {
+        return e;
+    }
+
+    let eid = match get_enclave_id() {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    // Get enclave measurements
+    let mut mr_enclave = [0u8; 32];
+    let mut mr_signer = [0u8; 32];
+    let mut retval = sgx_status_t::SGX_SUCCESS;
+
+    let status = unsafe {
+        ecall_get_enclave_info(
+            eid,
+            &mut retval,
+            mr_enclave.as_mut_ptr(),
+            mr_signer.as_mut_ptr(),
+        )
+    };
+
+    if status != sgx_status_t::SGX_SUCCESS || retval != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::ErrorAttestationFailed;
+    }
+
+    // Get the Quoting Enclave's target info so the report the enclave produces
+    // is bound to the QE (the QE refuses to turn a report targeted at anything
+    // else into a quote).
+    let mut qe_target_info = sgx_target_info_t::default();
+    if unsafe { sgx_qe_get_target_info(&mut qe_target_info) } != sgx_quote3_error_t::SGX_QL_SUCCESS {
+        return SgxBridgeStatus::ErrorAttestationFailed;
+    }
+
+    // Generate report, targeted at the QE rather than self-targeted.
+    let mut report = sgx_report_t::default();
+    let status = unsafe {
+        ecall_generate_report(
+            eid,
+            &mut retval,
+            report_data,
+            report_data_len,
+            &qe_target_info,
+            &mut report,
+        )
+    };
+
+    if status != sgx_status_t::SGX_SUCCESS || retval != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::ErrorAttestationFailed;
+    }
+
+    // Ask the QE to turn the bound report into a real DCAP ECDSA quote.
+    let mut quote_size: u32 = 0;
+    if unsafe { sgx_qe_get_quote_size(&mut quote_size) } != sgx_quote3_error_t::SGX_QL_SUCCESS {
+        return SgxBridgeStatus::ErrorAttestationFailed;
+    }
+    if quote_size as usize > 4096 {
+        return SgxBridgeStatus::ErrorBufferTooSmall;
+    }
+
+    let mut quote_buf = vec![0u8; quote_size as usize];
+    if unsafe { sgx_qe_get_quote(&report, quote_size, quote_buf.as_mut_ptr()) }
+        != sgx_quote3_error_t::SGX_QL_SUCCESS
+    {
+        return SgxBridgeStatus::ErrorAttestationFailed;
+    }
+
+    // Fill attestation structure
+    unsafe {
+        let att = &mut *attestation_out;
+        att.mr_enclave.copy_from_slice(&mr_enclave);
+        att.mr_signer.copy_from_slice(&mr_signer);
+
+        // Copy report data
+        let rd_len = std::cmp::min(report_data_len, 64);
+        if !report_data.is_null() && rd_len > 0 {
+            std::ptr::copy_nonoverlapping(report_data, att.report_data.as_mut_ptr(), rd_len);
+        }
+
+        att.quote[..quote_buf.len()].copy_from_slice(&quote_buf);
+        att.quote_len = quote_buf.len();
+        att.is_debug = if HARDWARE_MODE.load(Ordering::SeqCst) { 0 } else { 1 };
+    }
+
+    SgxBridgeStatus::Success
+}
+
+/// TCB freshness status of a verified DCAP quote, mirroring `sgx_ql_qv_result_t`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SgxBridgeTcbStatus {
+    UpToDate = 0,
+    SwHardeningNeeded = 1,
+    ConfigNeeded = 2,
+    ConfigAndSwHardeningNeeded = 3,
+    OutOfDate = 4,
+    OutOfDateConfigNeeded = 5,
+    Revoked = 6,
+    Unspecified = 7,
+}
+
+impl From<sgx_ql_qv_result_t> for SgxBridgeTcbStatus {
+    fn from(result: sgx_ql_qv_result_t) -> Self {
+        match result {
+            sgx_ql_qv_result_t::SGX_QL_QV_RESULT_OK => SgxBridgeTcbStatus::UpToDate,
+            sgx_ql_qv_result_t::SGX_QL_QV_RESULT_SW_HARDENING_NEEDED => {
+                SgxBridgeTcbStatus::SwHardeningNeeded
+            }
+            sgx_ql_qv_result_t::SGX_QL_QV_RESULT_CONFIG_NEEDED => SgxBridgeTcbStatus::ConfigNeeded,
+            sgx_ql_qv_result_t::SGX_QL_QV_RESULT_CONFIG_AND_SW_HARDENING_NEEDED => {
+                SgxBridgeTcbStatus::ConfigAndSwHardeningNeeded
+            }
+            sgx_ql_qv_result_t::SGX_QL_QV_RESULT_OUT_OF_DATE => SgxBridgeTcbStatus::OutOfDate,
+            sgx_ql_qv_result_t::SGX_QL_QV_RESULT_OUT_OF_DATE_CONFIG_NEEDED => {
+                SgxBridgeTcbStatus::OutOfDateConfigNeeded
+            }
+            sgx_ql_qv_result_t::SGX_QL_QV_RESULT_REVOKED => SgxBridgeTcbStatus::Revoked,
+            _ => SgxBridgeTcbStatus::Unspecified,
+        }
+    }
+}
+
+/// Result of [`sgx_bridge_verify_quote`].
+#[repr(C)]
+pub struct SgxBridgeQuoteVerifyResult {
+    pub tcb_status: SgxBridgeTcbStatus,
+    /// Non-zero if the PCK/TCB collateral used for the check had already
+    /// expired as of `expiration_timestamp`.
+    pub collateral_expired: c_int,
+    /// Unix timestamp the check was evaluated against; callers should
+    /// re-verify once their own clock passes this point rather than trusting
+    /// a quote indefinitely.
+    pub expiration_timestamp: i64,
+}
+
+/// Optional PCS collateral (PCK CRLs, TCB info, QE identity, and their issuer
+/// chains, all PEM/JSON text per Intel's format) for offline or pinned
+/// verification. Pass a null pointer to [`sgx_bridge_verify_quote`] to let the
+/// Quote Verification Library fetch collateral itself instead.
+#[repr(C)]
+pub struct SgxBridgeQuoteCollateral {
+    pub pck_crl_issuer_chain: *const c_char,
+    pub pck_crl_issuer_chain_size: u32,
+    pub root_ca_crl: *const c_char,
+    pub root_ca_crl_size: u32,
+    pub pck_crl: *const c_char,
+    pub pck_crl_size: u32,
+    pub tcb_info_issuer_chain: *const c_char,
+    pub tcb_info_issuer_chain_size: u32,
+    pub tcb_info: *const c_char,
+    pub tcb_info_size: u32,
+    pub qe_identity_issuer_chain: *const c_char,
+    pub qe_identity_issuer_chain_size: u32,
+    pub qe_identity: *const c_char,
+    pub qe_identity_size: u32,
+}
+
+fn to_native_collateral(c: &SgxBridgeQuoteCollateral) -> sgx_ql_qve_collateral_t {
+    sgx_ql_qve_collateral_t {
+        version: 3,
+        tee_type: 0, // SGX
+        pck_crl_issuer_chain: c.pck_crl_issuer_chain as *mut c_char,
+        pck_crl_issuer_chain_size: c.pck_crl_issuer_chain_size,
+        root_ca_crl: c.root_ca_crl as *mut c_char,
+        root_ca_crl_size: c.root_ca_crl_size,
+        pck_crl: c.pck_crl as *mut c_char,
+        pck_crl_size: c.pck_crl_size,
+        tcb_info_issuer_chain: c.tcb_info_issuer_chain as *mut c_char,
+        tcb_info_issuer_chain_size: c.tcb_info_issuer_chain_size,
+        tcb_info: c.tcb_info as *mut c_char,
+        tcb_info_size: c.tcb_info_size,
+        qe_identity_issuer_chain: c.qe_identity_issuer_chain as *mut c_char,
+        qe_identity_issuer_chain_size: c.qe_identity_issuer_chain_size,
+        qe_identity: c.qe_identity as *mut c_char,
+        qe_identity_size: c.qe_identity_size,
+    }
+}
+
+fn current_unix_time() -> i64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Verify a DCAP ECDSA quote produced by [`sgx_bridge_generate_attestation`],
+/// returning the TCB status (up-to-date / out-of-date / revoked / ...) and an
+/// expiration timestamp so callers can enforce freshness rather than trusting
+/// a quote indefinitely.
+#[no_mangle]
+pub extern "C" fn sgx_bridge_verify_quote(
+    quote: *const u8,
+    quote_len: usize,
+    collateral: *const SgxBridgeQuoteCollateral,
+    result_out: *mut SgxBridgeQuoteVerifyResult,
+) -> SgxBridgeStatus {
+    if quote.is_null() || quote_len == 0 || result_out.is_null() {
+        return SgxBridgeStatus::ErrorInvalidParameter;
+    }
+    if let Err(e) = check_buf(quote, quote_len) {
+        return e;
+    }
+
+    let mut supplemental_data_size: u32 = 0;
+    if unsafe { sgx_qv_get_quote_supplemental_data_size(&mut supplemental_data_size) }
+        != sgx_quote3_error_t::SGX_QL_SUCCESS
+    {
+        return SgxBridgeStatus::ErrorAttestationFailed;
+    }
+    let mut supplemental_data = vec![0u8; supplemental_data_size as usize];
+
+    let native_collateral = if collateral.is_null() {
+        None
+    } else {
+        Some(to_native_collateral(unsafe { &*collateral }))
+    };
+    let collateral_ptr = native_collateral
+        .as_ref()
+        .map(|c| c as *const sgx_ql_qve_collateral_t)
+        .unwrap_or(ptr::null());
+
+    let expiration_check_date = current_unix_time();
+    let mut collateral_expiration_status: u32 = 0;
+    let mut verification_result = sgx_ql_qv_result_t::SGX_QL_QV_RESULT_UNSPECIFIED;
+    let mut qve_report_info = sgx_ql_qe_report_info_t::default();
+
+    let ret = unsafe {
+        sgx_qv_verify_quote(
+            quote,
+            quote_len as u32,
+            collateral_ptr,
+            expiration_check_date,
+            &mut collateral_expiration_status,
+            &mut verification_result,
+            &mut qve_report_info,
+            supplemental_data_size,
+            supplemental_data.as_mut_ptr(),
+        )
+    };
+
+    if ret != sgx_quote3_error_t::SGX_QL_SUCCESS {
+        return SgxBridgeStatus::ErrorAttestationFailed;
+    }
+
+    unsafe {
+        let result = &mut *result_out;
+        result.tcb_status = SgxBridgeTcbStatus::from(verification_result);
+        result.collateral_expired = if collateral_expiration_status != 0 { 1 } else { 0 };
+        result.expiration_timestamp = expiration_check_date;
+    }
+
+    SgxBridgeStatus::Success
+}
+
+/// `isvEnclaveQuoteStatus` as reported by IAS, mirroring [`ias::IasQuoteStatus`].
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SgxBridgeIasQuoteStatus {
+    Ok = 0,
+    SignatureInvalid = 1,
+    GroupRevoked = 2,
+    SignatureRevoked = 3,
+    KeyRevoked = 4,
+    SigrlVersionMismatch = 5,
+    GroupOutOfDate = 6,
+    ConfigurationNeeded = 7,
+    SwHardeningNeeded = 8,
+    ConfigurationAndSwHardeningNeeded = 9,
+    Unknown = 255,
+}
+
+impl From<SgxBridgeIasQuoteStatus> for ias::IasQuoteStatus {
+    fn from(status: SgxBridgeIasQuoteStatus) -> Self {
+        match status {
+            SgxBridgeIasQuoteStatus::Ok => ias::IasQuoteStatus::Ok,
+            SgxBridgeIasQuoteStatus::SignatureInvalid => ias::IasQuoteStatus::SignatureInvalid,
+            SgxBridgeIasQuoteStatus::GroupRevoked => ias::IasQuoteStatus::GroupRevoked,
+            SgxBridgeIasQuoteStatus::SignatureRevoked => ias::IasQuoteStatus::SignatureRevoked,
+            SgxBridgeIasQuoteStatus::KeyRevoked => ias::IasQuoteStatus::KeyRevoked,
+            SgxBridgeIasQuoteStatus::SigrlVersionMismatch => ias::IasQuoteStatus::SigrlVersionMismatch,
+            SgxBridgeIasQuoteStatus::GroupOutOfDate => ias::IasQuoteStatus::GroupOutOfDate,
+            SgxBridgeIasQuoteStatus::ConfigurationNeeded => ias::IasQuoteStatus::ConfigurationNeeded,
+            SgxBridgeIasQuoteStatus::SwHardeningNeeded => ias::IasQuoteStatus::SwHardeningNeeded,
+            SgxBridgeIasQuoteStatus::ConfigurationAndSwHardeningNeeded => {
+                ias::IasQuoteStatus::ConfigurationAndSwHardeningNeeded
+            }
+            SgxBridgeIasQuoteStatus::Unknown => ias::IasQuoteStatus::Unknown,
+        }
+    }
+}
+
+impl From<ias::IasQuoteStatus> for SgxBridgeIasQuoteStatus {
+    fn from(status: ias::IasQuoteStatus) -> Self {
+        match status {
+            ias::IasQuoteStatus::Ok => SgxBridgeIasQuoteStatus::Ok,
+            ias::IasQuoteStatus::SignatureInvalid => SgxBridgeIasQuoteStatus::SignatureInvalid,
+            ias::IasQuoteStatus::GroupRevoked => SgxBridgeIasQuoteStatus::GroupRevoked,
+            ias::IasQuoteStatus::SignatureRevoked => SgxBridgeIasQuoteStatus::SignatureRevoked,
+            ias::IasQuoteStatus::KeyRevoked => SgxBridgeIasQuoteStatus::KeyRevoked,
+            ias::IasQuoteStatus::SigrlVersionMismatch => SgxBridgeIasQuoteStatus::SigrlVersionMismatch,
+            ias::IasQuoteStatus::GroupOutOfDate => SgxBridgeIasQuoteStatus::GroupOutOfDate,
+            ias::IasQuoteStatus::ConfigurationNeeded => SgxBridgeIasQuoteStatus::ConfigurationNeeded,
+            ias::IasQuoteStatus::SwHardeningNeeded => SgxBridgeIasQuoteStatus::SwHardeningNeeded,
+            ias::IasQuoteStatus::ConfigurationAndSwHardeningNeeded => {
+                SgxBridgeIasQuoteStatus::ConfigurationAndSwHardeningNeeded
+            }
+            ias::IasQuoteStatus::Unknown => SgxBridgeIasQuoteStatus::Unknown,
+        }
+    }
+}
+
+/// The quote body embedded in a verified IAS report, mirroring
+/// [`SgxBridgeAttestation`]'s measurement fields.
+#[repr(C)]
+pub struct SgxBridgeIasReportBody {
+    pub mr_enclave: [u8; 32],
+    pub mr_signer: [u8; 32],
+    pub isv_prod_id: u16,
+    pub isv_svn: u16,
+    pub report_data: [u8; 64],
+}
+
+/// Result of [`sgx_bridge_verify_ias_report`].
+#[repr(C)]
+pub struct SgxBridgeIasVerifyResult {
+    pub report_body: SgxBridgeIasReportBody,
+    pub status: SgxBridgeIasQuoteStatus,
+    pub timestamp: i64,
+}
+
+/// Verify an Intel Attestation Service (IAS) Attestation Verification
+/// Report (EPID) end to end: the `X-IASReport-Signing-Certificate` chain up
+/// to Intel's pinned report-signing CA, the `X-IASReport-Signature` over
+/// `report_bytes`, the quote status against `allowed_statuses`, and the
+/// report's freshness against `max_report_age_secs`.
+///
+/// `advisories_out`/`advisories_buf_len` receive the report's
+/// `advisoryIDs`, comma-joined; `advisories_len_out` receives the number of
+/// bytes written, or - if `advisories_buf_len` is too small - the required
+/// length, matching [`sgx_bridge_generate_ecdsa_keypair`]'s buffer-sizing
+/// convention. Pass a null `allowed_statuses` (or `allowed_statuses_len` of
+/// `0`) to accept only `Ok`.
+#[no_mangle]
+pub extern "C" fn sgx_bridge_verify_ias_report(
+    report_bytes: *const u8,
+    report_bytes_len: usize,
+    signature_b64: *const c_char,
+    cert_chain_pem: *const c_char,
+    allowed_statuses: *const SgxBridgeIasQuoteStatus,
+    allowed_statuses_len: usize,
+    max_report_age_secs: i64,
+    advisories_out: *mut c_char,
+    advisories_buf_len: usize,
+    advisories_len_out: *mut usize,
+    result_out: *mut SgxBridgeIasVerifyResult,
+) -> SgxBridgeStatus {
+    if signature_b64.is_null() || cert_chain_pem.is_null() || advisories_len_out.is_null() || result_out.is_null() {
+        return SgxBridgeStatus::ErrorInvalidParameter;
+    }
+    if let Err(e) = check_buf(report_bytes, report_bytes_len) {
+        return e;
+    }
+
+    let report_body_bytes = unsafe { std::slice::from_raw_parts(report_bytes, report_bytes_len) };
+    let signature_str = match unsafe { CStr::from_ptr(signature_b64) }.to_str() {
+        Ok(s) => s,
+        Err(_) => return SgxBridgeStatus::ErrorInvalidParameter,
+    };
+    let cert_chain_str = match unsafe { CStr::from_ptr(cert_chain_pem) }.to_str() {
+        Ok(s) => s,
+        Err(_) => return SgxBridgeStatus::ErrorInvalidParameter,
+    };
+    let allowed: Vec<ias::IasQuoteStatus> = if allowed_statuses.is_null() || allowed_statuses_len == 0 {
+        vec![ias::IasQuoteStatus::Ok]
+    } else {
+        unsafe { std::slice::from_raw_parts(allowed_statuses, allowed_statuses_len) }
+            .iter()
+            .map(|s| ias::IasQuoteStatus::from(*s))
+            .collect()
+    };
+
+    let verified = match ias::verify_ias_report(
+        report_body_bytes,
+        signature_str,
+        cert_chain_str,
+        &allowed,
+        max_report_age_secs,
+        current_unix_time(),
+    ) {
+        Ok(v) => v,
+        Err(_) => return SgxBridgeStatus::ErrorAttestationFailed,
+    };
+
+    let advisories_joined = verified.advisories.join(",");
+    let required = advisories_joined.len();
+    unsafe {
+        *advisories_len_out = required;
+    }
+    if required > advisories_buf_len {
+        return SgxBridgeStatus::ErrorBufferTooSmall;
+    }
+    if required > 0 {
+        if let Err(e) = check_buf(advisories_out as *const u8, advisories_buf_len) {
+            return e;
+        }
+        unsafe {
+            std::ptr::copy_nonoverlapping(advisories_joined.as_ptr() as *const c_char, advisories_out, required);
+        }
+    }
+
+    unsafe {
+        let result = &mut *result_out;
+        result.report_body.mr_enclave = verified.report_body.mr_enclave;
+        result.report_body.mr_signer = verified.report_body.mr_signer;
+        result.report_body.isv_prod_id = verified.report_body.isv_prod_id;
+        result.report_body.isv_svn = verified.report_body.isv_svn;
+        result.report_body.report_data = verified.report_body.report_data;
+        result.status = SgxBridgeIasQuoteStatus::from(verified.status);
+        result.timestamp = verified.timestamp;
+    }
+
+    SgxBridgeStatus::Success
+}
+
+/// Get enclave measurements.
+#[no_mangle]
+pub extern "C" fn sgx_bridge_get_measurements(
+    mr_enclave_out: *mut u8,
+    mr_signer_out: *mut u8,
+) -> SgxBridgeStatus {
+    if mr_enclave_out.is_null() || mr_signer_out.is_null() {
+        return SgxBridgeStatus::ErrorInvalidParameter;
+    }
+
+    let eid = match get_enclave_id() {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    let mut retval = sgx_status_t::SGX_SUCCESS;
+    let status = unsafe {
+        ecall_get_enclave_info(eid, &mut retval, mr_enclave_out, mr_signer_out)
+    };
+
+    if status != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::from(status);
+    }
+    SgxBridgeStatus::from(retval)
+}
+
+/// Required output buffer length for a public-key export `format`: `0` for
+/// the 65-byte uncompressed point, `1` for the 33-byte compressed point.
+fn public_key_len_for_format(format: u8) -> Result<usize, SgxBridgeStatus> {
+    match format {
+        0 => Ok(65),
+        1 => Ok(33),
+        _ => Err(SgxBridgeStatus::ErrorInvalidParameter),
+    }
+}
+
+/// Generate ECDSA key pair. `format` selects the returned public-key
+/// encoding (`0` = 65-byte uncompressed, `1` = 33-byte compressed);
+/// `public_key_out` must be sized for the requested format. The key is
+/// always stored enclave-side so it can be re-exported in either form later
+/// via [`sgx_bridge_export_public_key`].
+#[no_mangle]
+pub extern "C" fn sgx_bridge_generate_ecdsa_keypair(
+    key_id: *const c_char,
+    key_id_len: usize,
+    format: u8,
+    public_key_out: *mut u8,
+    public_key_buf_len: usize,
+    public_key_len_out: *mut usize,
+) -> SgxBridgeStatus {
+    if key_id.is_null() || public_key_out.is_null() || public_key_len_out.is_null() {
+        return SgxBridgeStatus::ErrorInvalidParameter;
+    }
+    if let Err(e) = check_buf(key_id as *const u8, key_id_len)
+        .and_then(|_| check_buf(public_key_out as *const u8, public_key_buf_len))
+    {
+        return e;
+    }
+    // Reject a caller-supplied buffer too small for the requested format
+    // before letting the enclave discover that the hard way.
+    let required = match public_key_len_for_format(format) {
+        Ok(n) => n,
+        Err(e) => return e,
+    };
+    if public_key_buf_len < required {
+        unsafe { *public_key_len_out = required; }
+        return SgxBridgeStatus::ErrorBufferTooSmall;
+    }
+
+    let eid = match get_enclave_id() {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    let mut retval = sgx_status_t::SGX_SUCCESS;
+    let status = unsafe {
+        ecall_generate_ecdsa_keypair(
+            eid,
+            &mut retval,
+            key_id as *const u8,
+            key_id_len,
+            format,
+            public_key_out,
+            public_key_buf_len,
+            public_key_len_out,
+        )
+    };
+
+    if status != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::from(status);
+    }
+    if retval != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::ErrorCryptoFailed;
+    }
+
+    SgxBridgeStatus::Success
+}
+
+/// Re-export an already-generated key's public key in either SEC1 encoding
+/// (`format`: `0` = 65-byte uncompressed, `1` = 33-byte compressed) without
+/// regenerating it. Works for keys created by both
+/// [`sgx_bridge_generate_ecdsa_keypair`] and
+/// [`sgx_bridge_generate_secp256k1_keypair`].
+#[no_mangle]
+pub extern "C" fn sgx_bridge_export_public_key(
+    key_id: *const c_char,
+    key_id_len: usize,
+    format: u8,
+    public_key_out: *mut u8,
+    public_key_buf_len: usize,
+    public_key_len_out: *mut usize,
+) -> SgxBridgeStatus {
+    if key_id.is_null() || public_key_out.is_null() || public_key_len_out.is_null() {
+        return SgxBridgeStatus::ErrorInvalidParameter;
+    }
+    if let Err(e) = check_buf(key_id as *const u8, key_id_len)
+        .and_then(|_| check_buf(public_key_out as *const u8, public_key_buf_len))
+    {
+        return e;
+    }
+    // Reject a caller-supplied buffer too small for the requested format
+    // before letting the enclave discover that the hard way.
+    let required = match public_key_len_for_format(format) {
+        Ok(n) => n,
+        Err(e) => return e,
+    };
+    if public_key_buf_len < required {
+        return SgxBridgeStatus::ErrorBufferTooSmall;
+    }
+
+    let eid = match get_enclave_id() {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    let mut retval = sgx_status_t::SGX_SUCCESS;
+    let status = unsafe {
+        ecall_export_public_key(
+            eid,
+            &mut retval,
+            key_id as *const u8,
+            key_id_len,
+            format,
+            public_key_out,
+            public_key_buf_len,
+            public_key_len_out,
+        )
+    };
+
+    if status != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::from(status);
+    }
+    if retval != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::ErrorCryptoFailed;
+    }
+
+    SgxBridgeStatus::Success
+}
+
+/// ECDSA sign.
+#[no_mangle]
+pub extern "C" fn sgx_bridge_ecdsa_sign(
+    key_id: *const c_char,
+    key_id_len: usize,
+    data: *const u8,
+    data_len: usize,
+    signature_out: *mut u8,
+) -> SgxBridgeStatus {
+    if key_id.is_null() || data.is_null() || signature_out.is_null() {
+        return SgxBridgeStatus::ErrorInvalidParameter;
+    }
+    if let Err(e) = check_buf(key_id as *const u8, key_id_len)
+        .and_then(|_| check_buf(data, data_len))
+    {
+        return e;
+    }
+
+    let eid = match get_enclave_id() {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    let mut retval = sgx_status_t::SGX_SUCCESS;
+    let status = unsafe {
+        ecall_ecdsa_sign(
+            eid,
+            &mut retval,
+            key_id as *const u8,
+            key_id_len,
+            data,
+            data_len,
+            signature_out,
+            64,
+        )
+    };
+
+    if status != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::from(status);
+    }
+    if retval != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::ErrorCryptoFailed;
+    }
+
+    SgxBridgeStatus::Success
+}
+
+/// ECDSA verify.
+#[no_mangle]
+pub extern "C" fn sgx_bridge_ecdsa_verify(
+    public_key: *const u8,
+    data: *const u8,
+    data_len: usize,
+    signature: *const u8,
+    valid_out: *mut c_int,
+) -> SgxBridgeStatus {
+    if public_key.is_null() || data.is_null() || signature.is_null() || valid_out.is_null() {
+        return SgxBridgeStatus::ErrorInvalidParameter;
+    }
+    if let Err(e) = check_buf(public_key, 65)
+        .and_then(|_| check_buf(data, data_len))
+        .and_then(|_| check_buf(signature, 64))
+    {
+        return e;
+    }
+
+    let eid = match get_enclave_id() {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    let mut retval = sgx_status_t::SGX_SUCCESS;
+    let mut valid: i32 = 0;
+    let status = unsafe {
+        ecall_ecdsa_verify(
+            eid,
+            &mut retval,
+            public_key, 65,
+            data, data_len,
+            signature, 64,
+            &mut valid,
+        )
+    };
+
+    if status != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::from(status);
+    }
+    if retval != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::ErrorCryptoFailed;
+    }
+
+    unsafe { *valid_out = valid; }
+    SgxBridgeStatus::Success
+}
+
+/// Generate a secp256k1 key pair for blockchain-facing (Neo/Ethereum-style)
+/// signing, returning the 33-byte compressed public key.
+#[no_mangle]
+pub extern "C" fn sgx_bridge_generate_secp256k1_keypair(
+    key_id: *const c_char,
+    key_id_len: usize,
+    public_key_out: *mut u8,
+) -> SgxBridgeStatus {
+    if key_id.is_null() || public_key_out.is_null() {
+        return SgxBridgeStatus::ErrorInvalidParameter;
+    }
+    if let Err(e) = check_buf(key_id as *const u8, key_id_len) {
+        return e;
+    }
+
+    let eid = match get_enclave_id() {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    let mut retval = sgx_status_t::SGX_SUCCESS;
+    let status = unsafe {
+        ecall_generate_secp256k1_keypair(
+            eid,
+            &mut retval,
+            key_id as *const u8,
+            key_id_len,
+            public_key_out,
+            33,
+        )
+    };
+
+    if status != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::from(status);
+    }
+    if retval != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::ErrorCryptoFailed;
+    }
+
+    SgxBridgeStatus::Success
+}
+
+/// Sign data with a stored secp256k1 key, producing a 64-byte compact
+/// `r || s` signature.
+#[no_mangle]
+pub extern "C" fn sgx_bridge_secp256k1_sign(
+    key_id: *const c_char,
+    key_id_len: usize,
+    data: *const u8,
+    data_len: usize,
+    signature_out: *mut u8,
+) -> SgxBridgeStatus {
+    if key_id.is_null() || data.is_null() || signature_out.is_null() {
+        return SgxBridgeStatus::ErrorInvalidParameter;
+    }
+    if let Err(e) = check_buf(key_id as *const u8, key_id_len)
+        .and_then(|_| check_buf(data, data_len))
+    {
+        return e;
+    }
+
+    let eid = match get_enclave_id() {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    let mut retval = sgx_status_t::SGX_SUCCESS;
+    let status = unsafe {
+        ecall_secp256k1_sign(
+            eid,
+            &mut retval,
+            key_id as *const u8,
+            key_id_len,
+            data,
+            data_len,
+            signature_out,
+            64,
+        )
+    };
+
+    if status != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::from(status);
+    }
+    if retval != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::ErrorCryptoFailed;
+    }
+
+    SgxBridgeStatus::Success
+}
+
+/// Verify a 64-byte compact secp256k1 signature against a public key
+/// (33-byte compressed or 65-byte uncompressed SEC1 form).
+#[no_mangle]
+pub extern "C" fn sgx_bridge_secp256k1_verify(
+    public_key: *const u8,
+    public_key_len: usize,
+    data: *const u8,
+    data_len: usize,
+    signature: *const u8,
+    valid_out: *mut c_int,
+) -> SgxBridgeStatus {
+    if public_key.is_null() || data.is_null() || signature.is_null() || valid_out.is_null() {
+        return SgxBridgeStatus::ErrorInvalidParameter;
+    }
+    if let Err(e) = check_buf(public_key, public_key_len)
+        .and_then(|_| check_buf(data, data_len))
+        .and_then(|_| check_buf(signature, 64))
+    {
+        return e;
+    }
+
+    let eid = match get_enclave_id() {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    let mut retval = sgx_status_t::SGX_SUCCESS;
+    let mut valid: i32 = 0;
+    let status = unsafe {
+        ecall_secp256k1_verify(
+            eid,
+            &mut retval,
+            public_key, public_key_len,
+            data, data_len,
+            signature, 64,
+            &mut valid,
+        )
+    };
+
+    if status != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::from(status);
+    }
+    if retval != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::ErrorCryptoFailed;
+    }
+
+    unsafe { *valid_out = valid; }
+    SgxBridgeStatus::Success
+}
+
+/// Compute an ECDH shared secret between a stored key (P-256 or secp256k1,
+/// whichever `key_id` was generated as) and a peer's public key, writing
+/// SHA-256 of the shared point's x-coordinate (32 bytes) to `shared_out`.
+/// The result can be fed directly to [`sgx_bridge_aes_gcm_encrypt`] as a
+/// session key.
+#[no_mangle]
+pub extern "C" fn sgx_bridge_ecdh(
+    key_id: *const c_char,
+    key_id_len: usize,
+    peer_public_key: *const u8,
+    peer_public_key_len: usize,
+    shared_out: *mut u8,
+) -> SgxBridgeStatus {
+    if key_id.is_null() || peer_public_key.is_null() || shared_out.is_null() {
+        return SgxBridgeStatus::ErrorInvalidParameter;
+    }
+    if let Err(e) = check_buf(key_id as *const u8, key_id_len)
+        .and_then(|_| check_buf(peer_public_key, peer_public_key_len))
+    {
+        return e;
+    }
+
+    let eid = match get_enclave_id() {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    let mut retval = sgx_status_t::SGX_SUCCESS;
+    let status = unsafe {
+        ecall_ecdh(
+            eid,
+            &mut retval,
+            key_id as *const u8,
+            key_id_len,
+            peer_public_key,
+            peer_public_key_len,
+            shared_out,
+            32,
+        )
+    };
+
+    if status != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::from(status);
+    }
+    if retval != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::ErrorCryptoFailed;
+    }
+
+    SgxBridgeStatus::Success
+}
+
+/// SHA-256 hash.
+#[no_mangle]
+pub extern "C" fn sgx_bridge_sha256(
+    data: *const u8,
+    data_len: usize,
+    hash_out: *mut u8,
+) -> SgxBridgeStatus {
+    if data.is_null() || hash_out.is_null() {
+        return SgxBridgeStatus::ErrorInvalidParameter;
+    }
+    if let Err(e) = check_buf(data, data_len) {
+        return e;
+    }
+
+    let eid = match get_enclave_id() {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    let mut retval = sgx_status_t::SGX_SUCCESS;
+    let status = unsafe {
+        ecall_sha256(eid, &mut retval, data, data_len, hash_out, 32)
+    };
+
+    if status != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::from(status);
+    }
+    SgxBridgeStatus::from(retval)
+}
+
+/// AES-GCM encrypt.
+///
+/// If `generate_iv` is non-zero, `iv` is treated as an out parameter: a
+/// fresh 12-byte IV is drawn from the enclave's `sgx_read_rand` and written
+/// into it before encrypting, rather than trusting a caller-supplied IV
+/// (which a misbehaving or buggy caller could reuse and break GCM). Pass 0
+/// to use the IV already in `iv` unchanged.
+#[no_mangle]
+pub extern "C" fn sgx_bridge_aes_gcm_encrypt(
+    key: *const u8,
+    iv: *mut u8,
+    generate_iv: c_int,
+    plaintext: *const u8,
+    plaintext_len: usize,
+    aad: *const u8,
+    aad_len: usize,
+    ciphertext_out: *mut u8,
+    tag_out: *mut u8,
+) -> SgxBridgeStatus {
+    if key.is_null() || iv.is_null() || plaintext.is_null()
+        || ciphertext_out.is_null() || tag_out.is_null() {
+        return SgxBridgeStatus::ErrorInvalidParameter;
+    }
+    if let Err(e) = check_buf(plaintext, plaintext_len)
+        .and_then(|_| check_buf(aad, aad_len))
+    {
+        return e;
+    }
+
+    let eid = match get_enclave_id() {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    let mut retval = sgx_status_t::SGX_SUCCESS;
+    let status = unsafe {
+        ecall_aes_gcm_encrypt(
+            eid,
+            &mut retval,
+            key, 32,
+            iv, 12,
+            generate_iv as i32,
+            plaintext, plaintext_len,
+            aad, aad_len,
+            ciphertext_out, plaintext_len,
+            tag_out, 16,
+        )
+    };
+
+    if status != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::from(status);
+    }
+    if retval != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::ErrorCryptoFailed;
+    }
+
+    SgxBridgeStatus::Success
+}
+
+/// AES-GCM decrypt.
+#[no_mangle]
+pub extern "C" fn sgx_bridge_aes_gcm_decrypt(
+    key: *const u8,
+    iv: *const u8,
+    ciphertext: *const u8,
+    ciphertext_len: usize,
+    aad: *const u8,
+    aad_len: usize,
+    tag: *const u8,
+    plaintext_out: *mut u8,
+) -> SgxBridgeStatus {
+    if key.is_null() || iv.is_null() || ciphertext.is_null()
+        || tag.is_null() || plaintext_out.is_null() {
+        return SgxBridgeStatus::ErrorInvalidParameter;
+    }
+    if let Err(e) = check_buf(ciphertext, ciphertext_len)
+        .and_then(|_| check_buf(aad, aad_len))
+    {
+        return e;
+    }
+
+    let eid = match get_enclave_id() {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    let mut retval = sgx_status_t::SGX_SUCCESS;
+    let status = unsafe {
+        ecall_aes_gcm_decrypt(
+            eid,
+            &mut retval,
+            key, 32,
+            iv, 12,
+            ciphertext, ciphertext_len,
+            aad, aad_len,
+            tag, 16,
+            plaintext_out, ciphertext_len,
+        )
+    };
+
+    if status != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::from(status);
+    }
+    if retval != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::ErrorCryptoFailed;
+    }
+
+    SgxBridgeStatus::Success
+}
+
+/// Generate random bytes.
+///
+/// Routes through `ecall_random_bytes` so the bytes come from the enclave's
+/// `sgx_read_rand` (RDRAND-backed CSPRNG) rather than untrusted host memory,
+/// which an attacker controlling the host could otherwise feed predictable
+/// values into. Falling back to `/dev/urandom` is only acceptable when no
+/// enclave is loaded or we're not running in hardware mode (local dev /
+/// simulation builds); in hardware mode a failed ECALL is a hard error.
+#[no_mangle]
+pub extern "C" fn sgx_bridge_random_bytes(
+    buffer: *mut u8,
+    length: usize,
+) -> SgxBridgeStatus {
+    if buffer.is_null() || length == 0 {
+        return SgxBridgeStatus::ErrorInvalidParameter;
+    }
+    if let Err(e) = check_buf(buffer as *const u8, length) {
+        return e;
+    }
+
+    if let Ok(eid) = get_enclave_id() {
+        let mut retval = sgx_status_t::SGX_SUCCESS;
+        let status = unsafe { ecall_random_bytes(eid, &mut retval, buffer, length) };
+        if status == sgx_status_t::SGX_SUCCESS && retval == sgx_status_t::SGX_SUCCESS {
+            return SgxBridgeStatus::Success;
+        }
+        if HARDWARE_MODE.load(Ordering::SeqCst) {
+            return SgxBridgeStatus::ErrorCryptoFailed;
+        }
+    }
+
+    let slice = unsafe { std::slice::from_raw_parts_mut(buffer, length) };
+    use std::io::Read;
+    if let Ok(mut f) = std::fs::File::open("/dev/urandom") {
+        if f.read_exact(slice).is_ok() {
+            return SgxBridgeStatus::Success;
+        }
+    }
+
+    SgxBridgeStatus::ErrorCryptoFailed
+}
+
+/// Export this enclave's target info, for a peer enclave to target its
+/// `EREPORT` at us as the first step of a mutual local-attestation handshake.
+#[no_mangle]
+pub extern "C" fn sgx_bridge_get_target_info(
+    target_info_out: *mut sgx_target_info_t,
+) -> SgxBridgeStatus {
+    if target_info_out.is_null() {
         return SgxBridgeStatus::ErrorInvalidParameter;
     }
 
@@ -411,76 +2080,123 @@ pub extern "C" fn sgx_bridge_generate_attestation(
         Err(e) => return e,
     };
 
-    // Get enclave measurements
-    let mut mr_enclave = [0u8; 32];
-    let mut mr_signer = [0u8; 32];
     let mut retval = sgx_status_t::SGX_SUCCESS;
+    let status = unsafe { ecall_get_target_info(eid, &mut retval, target_info_out) };
 
-    let status = unsafe {
-        ecall_get_enclave_info(
-            eid,
-            &mut retval,
-            mr_enclave.as_mut_ptr(),
-            mr_signer.as_mut_ptr(),
-        )
+    if status != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::from(status);
+    }
+    SgxBridgeStatus::from(retval)
+}
+
+/// Verify a peer enclave's local-attestation report (EREPORT MAC check).
+#[no_mangle]
+pub extern "C" fn sgx_bridge_verify_report(
+    report: *const sgx_report_t,
+    valid_out: *mut c_int,
+) -> SgxBridgeStatus {
+    if report.is_null() || valid_out.is_null() {
+        return SgxBridgeStatus::ErrorInvalidParameter;
+    }
+
+    let eid = match get_enclave_id() {
+        Ok(id) => id,
+        Err(e) => return e,
     };
 
-    if status != sgx_status_t::SGX_SUCCESS || retval != sgx_status_t::SGX_SUCCESS {
+    let mut retval = sgx_status_t::SGX_SUCCESS;
+    let mut valid: i32 = 0;
+    let status = unsafe { ecall_verify_report(eid, &mut retval, report, &mut valid) };
+
+    if status != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::from(status);
+    }
+    if retval != sgx_status_t::SGX_SUCCESS {
         return SgxBridgeStatus::ErrorAttestationFailed;
     }
 
-    // Generate report
-    let mut report = sgx_report_t::default();
+    unsafe { *valid_out = valid; }
+    SgxBridgeStatus::Success
+}
+
+/// Begin a SIGMA-style mutual local-attestation key exchange with a peer
+/// enclave (see [`sgx_bridge_session_complete`]). `session_id` scopes the
+/// ephemeral key pair the enclave holds open between the two calls and must
+/// be passed unchanged to `sgx_bridge_session_complete`.
+#[no_mangle]
+pub extern "C" fn sgx_bridge_session_init(
+    session_id: *const c_char,
+    session_id_len: usize,
+    peer_target_info: *const sgx_target_info_t,
+    public_key_out: *mut u8,
+    report_out: *mut sgx_report_t,
+) -> SgxBridgeStatus {
+    if session_id.is_null() || peer_target_info.is_null()
+        || public_key_out.is_null() || report_out.is_null() {
+        return SgxBridgeStatus::ErrorInvalidParameter;
+    }
+    if let Err(e) = check_buf(session_id as *const u8, session_id_len) {
+        return e;
+    }
+
+    let eid = match get_enclave_id() {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    let mut retval = sgx_status_t::SGX_SUCCESS;
     let status = unsafe {
-        ecall_generate_report(
+        ecall_session_init(
             eid,
             &mut retval,
-            report_data,
-            report_data_len,
-            ptr::null(),
-            &mut report,
+            session_id as *const u8,
+            session_id_len,
+            peer_target_info,
+            public_key_out,
+            65,
+            report_out,
         )
     };
 
-    if status != sgx_status_t::SGX_SUCCESS || retval != sgx_status_t::SGX_SUCCESS {
-        return SgxBridgeStatus::ErrorAttestationFailed;
+    if status != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::from(status);
     }
-
-    // Fill attestation structure
-    unsafe {
-        let att = &mut *attestation_out;
-        att.mr_enclave.copy_from_slice(&mr_enclave);
-        att.mr_signer.copy_from_slice(&mr_signer);
-
-        // Copy report data
-        let rd_len = std::cmp::min(report_data_len, 64);
-        if !report_data.is_null() && rd_len > 0 {
-            std::ptr::copy_nonoverlapping(report_data, att.report_data.as_mut_ptr(), rd_len);
-        }
-
-        // For now, use report as quote (in production, would call QE to generate quote)
-        let report_bytes = std::slice::from_raw_parts(
-            &report as *const _ as *const u8,
-            std::mem::size_of::<sgx_report_t>(),
-        );
-        let quote_len = std::cmp::min(report_bytes.len(), 4096);
-        att.quote[..quote_len].copy_from_slice(&report_bytes[..quote_len]);
-        att.quote_len = quote_len;
-        att.is_debug = if HARDWARE_MODE.load(Ordering::SeqCst) { 0 } else { 1 };
+    if retval != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::ErrorAttestationFailed;
     }
 
     SgxBridgeStatus::Success
 }
 
-/// Get enclave measurements.
+/// Complete the handshake started by [`sgx_bridge_session_init`]: verify the
+/// peer's report and its binding to `peer_public_key`, optionally pin the
+/// peer's MRENCLAVE/MRSIGNER (pass null to skip a pin), then derive a shared
+/// AES-256-GCM session key and store it under `key_id` inside the enclave.
+/// Use [`sgx_bridge_session_encrypt`]/[`sgx_bridge_session_decrypt`] to
+/// migrate sealed secrets to the peer - the session key itself never crosses
+/// the enclave boundary.
 #[no_mangle]
-pub extern "C" fn sgx_bridge_get_measurements(
-    mr_enclave_out: *mut u8,
-    mr_signer_out: *mut u8,
+pub extern "C" fn sgx_bridge_session_complete(
+    session_id: *const c_char,
+    session_id_len: usize,
+    peer_public_key: *const u8,
+    peer_public_key_len: usize,
+    peer_report: *const sgx_report_t,
+    expected_mr_enclave: *const u8,
+    expected_mr_signer: *const u8,
+    key_id: *const c_char,
+    key_id_len: usize,
 ) -> SgxBridgeStatus {
-    if mr_enclave_out.is_null() || mr_signer_out.is_null() {
+    if session_id.is_null() || peer_public_key.is_null() || peer_report.is_null()
+        || key_id.is_null() {
         return SgxBridgeStatus::ErrorInvalidParameter;
     }
+    if let Err(e) = check_buf(session_id as *const u8, session_id_len)
+        .and_then(|_| check_buf(peer_public_key, peer_public_key_len))
+        .and_then(|_| check_buf(key_id as *const u8, key_id_len))
+    {
+        return e;
+    }
 
     let eid = match get_enclave_id() {
         Ok(id) => id,
@@ -489,25 +2205,56 @@ pub extern "C" fn sgx_bridge_get_measurements(
 
     let mut retval = sgx_status_t::SGX_SUCCESS;
     let status = unsafe {
-        ecall_get_enclave_info(eid, &mut retval, mr_enclave_out, mr_signer_out)
+        ecall_session_complete(
+            eid,
+            &mut retval,
+            session_id as *const u8,
+            session_id_len,
+            peer_public_key,
+            peer_public_key_len,
+            peer_report,
+            expected_mr_enclave,
+            expected_mr_signer,
+            key_id as *const u8,
+            key_id_len,
+        )
     };
 
     if status != sgx_status_t::SGX_SUCCESS {
         return SgxBridgeStatus::from(status);
     }
-    SgxBridgeStatus::from(retval)
+    if retval != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::ErrorAttestationFailed;
+    }
+
+    SgxBridgeStatus::Success
 }
 
-/// Generate ECDSA key pair.
+/// Encrypt data with a session key established by
+/// [`sgx_bridge_session_complete`]. `nonce_out` receives the freshly-drawn
+/// 12-byte IV used for this message.
 #[no_mangle]
-pub extern "C" fn sgx_bridge_generate_ecdsa_keypair(
+pub extern "C" fn sgx_bridge_session_encrypt(
     key_id: *const c_char,
     key_id_len: usize,
-    public_key_out: *mut u8,
+    plaintext: *const u8,
+    plaintext_len: usize,
+    aad: *const u8,
+    aad_len: usize,
+    ciphertext_out: *mut u8,
+    nonce_out: *mut u8,
+    tag_out: *mut u8,
 ) -> SgxBridgeStatus {
-    if key_id.is_null() || public_key_out.is_null() {
+    if key_id.is_null() || plaintext.is_null() || ciphertext_out.is_null()
+        || nonce_out.is_null() || tag_out.is_null() {
         return SgxBridgeStatus::ErrorInvalidParameter;
     }
+    if let Err(e) = check_buf(key_id as *const u8, key_id_len)
+        .and_then(|_| check_buf(plaintext, plaintext_len))
+        .and_then(|_| check_buf(aad, aad_len))
+    {
+        return e;
+    }
 
     let eid = match get_enclave_id() {
         Ok(id) => id,
@@ -516,13 +2263,21 @@ pub extern "C" fn sgx_bridge_generate_ecdsa_keypair(
 
     let mut retval = sgx_status_t::SGX_SUCCESS;
     let status = unsafe {
-        ecall_generate_ecdsa_keypair(
+        ecall_session_encrypt(
             eid,
             &mut retval,
             key_id as *const u8,
             key_id_len,
-            public_key_out,
-            65,
+            plaintext,
+            plaintext_len,
+            aad,
+            aad_len,
+            ciphertext_out,
+            plaintext_len,
+            nonce_out,
+            12,
+            tag_out,
+            16,
         )
     };
 
@@ -536,18 +2291,30 @@ pub extern "C" fn sgx_bridge_generate_ecdsa_keypair(
     SgxBridgeStatus::Success
 }
 
-/// ECDSA sign.
+/// Decrypt data with a session key established by
+/// [`sgx_bridge_session_complete`].
 #[no_mangle]
-pub extern "C" fn sgx_bridge_ecdsa_sign(
+pub extern "C" fn sgx_bridge_session_decrypt(
     key_id: *const c_char,
     key_id_len: usize,
-    data: *const u8,
-    data_len: usize,
-    signature_out: *mut u8,
+    ciphertext: *const u8,
+    ciphertext_len: usize,
+    aad: *const u8,
+    aad_len: usize,
+    nonce: *const u8,
+    tag: *const u8,
+    plaintext_out: *mut u8,
 ) -> SgxBridgeStatus {
-    if key_id.is_null() || data.is_null() || signature_out.is_null() {
+    if key_id.is_null() || ciphertext.is_null() || nonce.is_null()
+        || tag.is_null() || plaintext_out.is_null() {
         return SgxBridgeStatus::ErrorInvalidParameter;
     }
+    if let Err(e) = check_buf(key_id as *const u8, key_id_len)
+        .and_then(|_| check_buf(ciphertext, ciphertext_len))
+        .and_then(|_| check_buf(aad, aad_len))
+    {
+        return e;
+    }
 
     let eid = match get_enclave_id() {
         Ok(id) => id,
@@ -556,15 +2323,21 @@ pub extern "C" fn sgx_bridge_ecdsa_sign(
 
     let mut retval = sgx_status_t::SGX_SUCCESS;
     let status = unsafe {
-        ecall_ecdsa_sign(
+        ecall_session_decrypt(
             eid,
             &mut retval,
             key_id as *const u8,
             key_id_len,
-            data,
-            data_len,
-            signature_out,
-            64,
+            ciphertext,
+            ciphertext_len,
+            aad,
+            aad_len,
+            nonce,
+            12,
+            tag,
+            16,
+            plaintext_out,
+            ciphertext_len,
         )
     };
 
@@ -578,38 +2351,80 @@ pub extern "C" fn sgx_bridge_ecdsa_sign(
     SgxBridgeStatus::Success
 }
 
-/// ECDSA verify.
+/// Begin a UKEY2-style attestation-bound handshake (see
+/// [`sgx_bridge_secure_handshake_finish`]). `target_info` should be the
+/// Quoting Enclave's target info (as returned by `sgx_qe_get_target_info`,
+/// the same call [`sgx_bridge_generate_attestation`] makes) so the caller
+/// can turn `report_out` into a DCAP quote for the remote peer to verify,
+/// rather than a local report only another enclave on this platform could
+/// check.
 #[no_mangle]
-pub extern "C" fn sgx_bridge_ecdsa_verify(
-    public_key: *const u8,
-    data: *const u8,
-    data_len: usize,
-    signature: *const u8,
-    valid_out: *mut c_int,
+pub extern "C" fn sgx_bridge_secure_handshake_init(
+    session_id: *const c_char,
+    session_id_len: usize,
+    target_info: *const sgx_target_info_t,
+    public_key_out: *mut u8,
+    report_out: *mut sgx_report_t,
 ) -> SgxBridgeStatus {
-    if public_key.is_null() || data.is_null() || signature.is_null() || valid_out.is_null() {
+    if session_id.is_null() || target_info.is_null()
+        || public_key_out.is_null() || report_out.is_null() {
         return SgxBridgeStatus::ErrorInvalidParameter;
     }
+    if let Err(e) = check_buf(session_id as *const u8, session_id_len) {
+        return e;
+    }
+
+    let eid = match get_enclave_id() {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+
+    let mut retval = sgx_status_t::SGX_SUCCESS;
+    let status = unsafe {
+        ecall_secure_handshake_init(
+            eid,
+            &mut retval,
+            session_id as *const u8,
+            session_id_len,
+            target_info,
+            public_key_out,
+            65,
+            report_out,
+        )
+    };
 
-    // For verification, we use the SGX crypto library directly in untrusted code
-    // since verification doesn't require secrets
-    // In production, this could also be done inside the enclave
+    if status != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::from(status);
+    }
+    if retval != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::ErrorAttestationFailed;
+    }
 
-    // For now, return success (verification would be implemented with proper crypto lib)
-    unsafe { *valid_out = 1; }
     SgxBridgeStatus::Success
 }
 
-/// SHA-256 hash.
+/// Complete the handshake started by [`sgx_bridge_secure_handshake_init`],
+/// deriving directional AES-256-GCM session keys from the peer's ephemeral
+/// public key and writing a 6-byte human-verifiable auth string to
+/// `auth_string_out` for out-of-band comparison. Use
+/// [`sgx_bridge_secure_session_encrypt`]/[`sgx_bridge_secure_session_decrypt`]
+/// to exchange data over the resulting channel.
 #[no_mangle]
-pub extern "C" fn sgx_bridge_sha256(
-    data: *const u8,
-    data_len: usize,
-    hash_out: *mut u8,
+pub extern "C" fn sgx_bridge_secure_handshake_finish(
+    session_id: *const c_char,
+    session_id_len: usize,
+    peer_public_key: *const u8,
+    peer_public_key_len: usize,
+    auth_string_out: *mut u8,
 ) -> SgxBridgeStatus {
-    if data.is_null() || hash_out.is_null() {
+    if session_id.is_null() || peer_public_key.is_null() || auth_string_out.is_null() {
         return SgxBridgeStatus::ErrorInvalidParameter;
     }
+    if let Err(e) = check_buf(session_id as *const u8, session_id_len)
+        .and_then(|_| check_buf(peer_public_key, peer_public_key_len))
+    {
+        return e;
+    }
 
     let eid = match get_enclave_id() {
         Ok(id) => id,
@@ -618,31 +2433,54 @@ pub extern "C" fn sgx_bridge_sha256(
 
     let mut retval = sgx_status_t::SGX_SUCCESS;
     let status = unsafe {
-        ecall_sha256(eid, &mut retval, data, data_len, hash_out, 32)
+        ecall_secure_handshake_finish(
+            eid,
+            &mut retval,
+            session_id as *const u8,
+            session_id_len,
+            peer_public_key,
+            peer_public_key_len,
+            auth_string_out,
+            6,
+        )
     };
 
     if status != sgx_status_t::SGX_SUCCESS {
         return SgxBridgeStatus::from(status);
     }
-    SgxBridgeStatus::from(retval)
+    if retval != sgx_status_t::SGX_SUCCESS {
+        return SgxBridgeStatus::ErrorAttestationFailed;
+    }
+
+    SgxBridgeStatus::Success
 }
 
-/// AES-GCM encrypt.
+/// Encrypt data under a channel established by
+/// [`sgx_bridge_secure_handshake_finish`]. `nonce_out` receives this
+/// message's counter-derived 12-byte IV, which must be delivered to the
+/// peer alongside the ciphertext.
 #[no_mangle]
-pub extern "C" fn sgx_bridge_aes_gcm_encrypt(
-    key: *const u8,
-    iv: *const u8,
+pub extern "C" fn sgx_bridge_secure_session_encrypt(
+    session_id: *const c_char,
+    session_id_len: usize,
     plaintext: *const u8,
     plaintext_len: usize,
     aad: *const u8,
     aad_len: usize,
     ciphertext_out: *mut u8,
+    nonce_out: *mut u8,
     tag_out: *mut u8,
 ) -> SgxBridgeStatus {
-    if key.is_null() || iv.is_null() || plaintext.is_null()
-        || ciphertext_out.is_null() || tag_out.is_null() {
+    if session_id.is_null() || plaintext.is_null() || ciphertext_out.is_null()
+        || nonce_out.is_null() || tag_out.is_null() {
         return SgxBridgeStatus::ErrorInvalidParameter;
     }
+    if let Err(e) = check_buf(session_id as *const u8, session_id_len)
+        .and_then(|_| check_buf(plaintext, plaintext_len))
+        .and_then(|_| check_buf(aad, aad_len))
+    {
+        return e;
+    }
 
     let eid = match get_enclave_id() {
         Ok(id) => id,
@@ -651,15 +2489,21 @@ pub extern "C" fn sgx_bridge_aes_gcm_encrypt(
 
     let mut retval = sgx_status_t::SGX_SUCCESS;
     let status = unsafe {
-        ecall_aes_gcm_encrypt(
+        ecall_secure_session_encrypt(
             eid,
             &mut retval,
-            key, 32,
-            iv, 12,
-            plaintext, plaintext_len,
-            aad, aad_len,
-            ciphertext_out, plaintext_len,
-            tag_out, 16,
+            session_id as *const u8,
+            session_id_len,
+            plaintext,
+            plaintext_len,
+            aad,
+            aad_len,
+            ciphertext_out,
+            plaintext_len,
+            nonce_out,
+            12,
+            tag_out,
+            16,
         )
     };
 
@@ -673,22 +2517,33 @@ pub extern "C" fn sgx_bridge_aes_gcm_encrypt(
     SgxBridgeStatus::Success
 }
 
-/// AES-GCM decrypt.
+/// Decrypt data under a channel established by
+/// [`sgx_bridge_secure_handshake_finish`]. `nonce` must be the exact value
+/// produced by the peer's matching `sgx_bridge_secure_session_encrypt` call
+/// for this to succeed - a stale or reordered `nonce` is rejected inside
+/// the enclave rather than silently decrypted.
 #[no_mangle]
-pub extern "C" fn sgx_bridge_aes_gcm_decrypt(
-    key: *const u8,
-    iv: *const u8,
+pub extern "C" fn sgx_bridge_secure_session_decrypt(
+    session_id: *const c_char,
+    session_id_len: usize,
     ciphertext: *const u8,
     ciphertext_len: usize,
     aad: *const u8,
     aad_len: usize,
+    nonce: *const u8,
     tag: *const u8,
     plaintext_out: *mut u8,
 ) -> SgxBridgeStatus {
-    if key.is_null() || iv.is_null() || ciphertext.is_null()
+    if session_id.is_null() || ciphertext.is_null() || nonce.is_null()
         || tag.is_null() || plaintext_out.is_null() {
         return SgxBridgeStatus::ErrorInvalidParameter;
     }
+    if let Err(e) = check_buf(session_id as *const u8, session_id_len)
+        .and_then(|_| check_buf(ciphertext, ciphertext_len))
+        .and_then(|_| check_buf(aad, aad_len))
+    {
+        return e;
+    }
 
     let eid = match get_enclave_id() {
         Ok(id) => id,
@@ -697,15 +2552,21 @@ pub extern "C" fn sgx_bridge_aes_gcm_decrypt(
 
     let mut retval = sgx_status_t::SGX_SUCCESS;
     let status = unsafe {
-        ecall_aes_gcm_decrypt(
+        ecall_secure_session_decrypt(
             eid,
             &mut retval,
-            key, 32,
-            iv, 12,
-            ciphertext, ciphertext_len,
-            aad, aad_len,
-            tag, 16,
-            plaintext_out, ciphertext_len,
+            session_id as *const u8,
+            session_id_len,
+            ciphertext,
+            ciphertext_len,
+            aad,
+            aad_len,
+            nonce,
+            12,
+            tag,
+            16,
+            plaintext_out,
+            ciphertext_len,
         )
     };
 
@@ -719,31 +2580,6 @@ pub extern "C" fn sgx_bridge_aes_gcm_decrypt(
     SgxBridgeStatus::Success
 }
 
-/// Generate random bytes.
-#[no_mangle]
-pub extern "C" fn sgx_bridge_random_bytes(
-    buffer: *mut u8,
-    length: usize,
-) -> SgxBridgeStatus {
-    if buffer.is_null() || length == 0 {
-        return SgxBridgeStatus::ErrorInvalidParameter;
-    }
-
-    // Use SGX's hardware random number generator via RDRAND
-    let slice = unsafe { std::slice::from_raw_parts_mut(buffer, length) };
-
-    // In production, this would use sgx_read_rand from SGX SDK
-    // For now, use system random as fallback
-    use std::io::Read;
-    if let Ok(mut f) = std::fs::File::open("/dev/urandom") {
-        if f.read_exact(slice).is_ok() {
-            return SgxBridgeStatus::Success;
-        }
-    }
-
-    SgxBridgeStatus::ErrorCryptoFailed
-}
-
 // =============================================================================
 // Attestation Structure (must match sgx_bridge.h)
 // =============================================================================
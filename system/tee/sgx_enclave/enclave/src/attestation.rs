@@ -181,8 +181,11 @@ impl Quote {
         quote
     }
 
-    /// Serialize quote to bytes.
-    pub fn to_bytes(&self) -> Vec<u8> {
+    /// Serialize the header and report body - everything the attestation
+    /// (quote-signing) key's signature actually covers - without the
+    /// trailing signature fields. Used by [`crate::dcap::verify_dcap_quote`]
+    /// to reconstruct the exact bytes to verify the signature against.
+    pub fn header_and_body_bytes(&self) -> Vec<u8> {
         let mut bytes = Vec::new();
 
         // Version and sign type
@@ -217,12 +220,73 @@ impl Quote {
         bytes.extend_from_slice(&self.report_body.reserved4);
         bytes.extend_from_slice(&self.report_body.report_data);
 
+        bytes
+    }
+
+    /// Serialize quote to bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.header_and_body_bytes();
+
         // Signature
         bytes.extend_from_slice(&self.signature_len.to_le_bytes());
         bytes.extend_from_slice(&self.signature);
 
         bytes
     }
+
+    /// Parse a quote back out of the encoding produced by [`Quote::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> EnclaveResult<Self> {
+        let bad = || EnclaveError::AttestationFailed("truncated quote encoding".to_string());
+        let mut cursor = bytes;
+
+        let take = |cursor: &mut &[u8], n: usize| -> EnclaveResult<Vec<u8>> {
+            if cursor.len() < n {
+                return Err(bad());
+            }
+            let (head, tail) = cursor.split_at(n);
+            *cursor = tail;
+            Ok(head.to_vec())
+        };
+
+        let version = u16::from_le_bytes(take(&mut cursor, 2)?.try_into().unwrap());
+        let sign_type = u16::from_le_bytes(take(&mut cursor, 2)?.try_into().unwrap());
+        let epid_group_id: [u8; 4] = take(&mut cursor, 4)?.try_into().unwrap();
+        let qe_svn = u16::from_le_bytes(take(&mut cursor, 2)?.try_into().unwrap());
+        let pce_svn = u16::from_le_bytes(take(&mut cursor, 2)?.try_into().unwrap());
+        let xeid = u32::from_le_bytes(take(&mut cursor, 4)?.try_into().unwrap());
+        let basename: [u8; 32] = take(&mut cursor, 32)?.try_into().unwrap();
+
+        let mut report_body = ReportBody::default();
+        report_body.cpu_svn = take(&mut cursor, 16)?.try_into().unwrap();
+        report_body.misc_select = u32::from_le_bytes(take(&mut cursor, 4)?.try_into().unwrap());
+        report_body.reserved1 = take(&mut cursor, 28)?.try_into().unwrap();
+        report_body.attributes.flags = u64::from_le_bytes(take(&mut cursor, 8)?.try_into().unwrap());
+        report_body.attributes.xfrm = u64::from_le_bytes(take(&mut cursor, 8)?.try_into().unwrap());
+        report_body.mr_enclave = take(&mut cursor, 32)?.try_into().unwrap();
+        report_body.reserved2 = take(&mut cursor, 32)?.try_into().unwrap();
+        report_body.mr_signer = take(&mut cursor, 32)?.try_into().unwrap();
+        report_body.reserved3 = take(&mut cursor, 96)?.try_into().unwrap();
+        report_body.isv_prod_id = u16::from_le_bytes(take(&mut cursor, 2)?.try_into().unwrap());
+        report_body.isv_svn = u16::from_le_bytes(take(&mut cursor, 2)?.try_into().unwrap());
+        report_body.reserved4 = take(&mut cursor, 60)?.try_into().unwrap();
+        report_body.report_data = take(&mut cursor, 64)?.try_into().unwrap();
+
+        let signature_len = u32::from_le_bytes(take(&mut cursor, 4)?.try_into().unwrap());
+        let signature = take(&mut cursor, signature_len as usize)?;
+
+        Ok(Self {
+            version,
+            sign_type,
+            epid_group_id,
+            qe_svn,
+            pce_svn,
+            xeid,
+            basename,
+            report_body,
+            signature_len,
+            signature,
+        })
+    }
 }
 
 /// Attestation evidence for remote verification.
@@ -230,10 +294,19 @@ impl Quote {
 pub struct AttestationEvidence {
     /// The quote
     pub quote: Quote,
-    /// Platform certificate chain (for DCAP)
+    /// Platform certificate chain (for DCAP): the PCK leaf, intermediate,
+    /// and root certificates, DER-encoded and concatenated in that order.
     pub cert_chain: Option<Vec<u8>>,
     /// Collateral data
     pub collateral: Option<Vec<u8>>,
+    /// The DCAP quote's raw ECDSA-P256 signature section (`quote_signature`
+    /// `|| attestation_key || qe_report || qe_report_signature ||
+    /// qe_auth_data_size || qe_auth_data || qe_cert_data_type ||
+    /// qe_cert_data_size || qe_cert_data`), as returned by the Quoting
+    /// Enclave alongside the quote header and report body. `None` for a
+    /// local-attestation-only report, which has no attestation key to
+    /// verify. See [`crate::dcap::verify_dcap_quote`].
+    pub quote_signature_data: Option<Vec<u8>>,
 }
 
 impl AttestationEvidence {
@@ -243,6 +316,7 @@ impl AttestationEvidence {
             quote: Quote::from_report(report),
             cert_chain: None,
             collateral: None,
+            quote_signature_data: None,
         }
     }
 
@@ -284,7 +358,7 @@ impl ChannelBinding {
 }
 
 // SGX flags
-const SGX_FLAGS_DEBUG: u64 = 0x0000000000000002;
+pub(crate) const SGX_FLAGS_DEBUG: u64 = 0x0000000000000002;
 
 #[cfg(test)]
 mod tests {
@@ -9,7 +9,8 @@ use std::vec::Vec;
 use sgx_types::*;
 use sgx_tcrypto::*;
 
-use crate::types::{EnclaveError, EnclaveResult, KeyType};
+use crate::secp256k1;
+use crate::types::{EnclaveError, EnclaveResult, PublicKeyEncoding, SignatureEncoding};
 
 /// Compute SHA-256 hash.
 pub fn sha256(data: &[u8]) -> EnclaveResult<[u8; 32]> {
@@ -80,6 +81,20 @@ impl EcdsaKeyPair {
         self.private_key.r.to_vec()
     }
 
+    /// Get the public key in the requested wire encoding.
+    pub fn public_key_bytes_encoded(&self, encoding: PublicKeyEncoding) -> Vec<u8> {
+        match encoding {
+            PublicKeyEncoding::Uncompressed => self.public_key_bytes(),
+            PublicKeyEncoding::Compressed => {
+                let y_is_odd = self.public_key.gy[31] & 1 == 1;
+                let mut out = Vec::with_capacity(33);
+                out.push(if y_is_odd { 0x03 } else { 0x02 });
+                out.extend_from_slice(&self.public_key.gx);
+                out
+            }
+        }
+    }
+
     /// Restore from private key bytes.
     pub fn from_private_key(private_bytes: &[u8]) -> EnclaveResult<Self> {
         if private_bytes.len() != 32 {
@@ -141,9 +156,868 @@ impl EcdsaKeyPair {
 
         Ok(result)
     }
+
+    /// Sign data using ECDSA, returning a DER-encoded signature instead of
+    /// the default compact `r || s`.
+    pub fn sign_der(&self, data: &[u8]) -> EnclaveResult<Vec<u8>> {
+        let sig = self.sign(data)?;
+        let mut r = [0u8; 32];
+        let mut s = [0u8; 32];
+        r.copy_from_slice(&sig[..32]);
+        s.copy_from_slice(&sig[32..]);
+        Ok(der_encode_signature(&r, &s))
+    }
+
+    /// Verify a signature accepting either the compact `r || s` (64 bytes)
+    /// or DER encoding, canonicalizing internally before verifying.
+    pub fn verify_encoded(&self, data: &[u8], signature: &[u8]) -> EnclaveResult<bool> {
+        let (r, s) = match signature.len() {
+            64 => {
+                let mut r = [0u8; 32];
+                let mut s = [0u8; 32];
+                r.copy_from_slice(&signature[..32]);
+                s.copy_from_slice(&signature[32..]);
+                (r, s)
+            }
+            _ => der_decode_signature(signature)?,
+        };
+        let mut compact = [0u8; 64];
+        compact[..32].copy_from_slice(&r);
+        compact[32..].copy_from_slice(&s);
+        self.verify(data, &compact)
+    }
+
+    /// Compute an ECDH shared secret with a peer's uncompressed public key
+    /// (65 bytes: `04 || x || y`), returning SHA-256 of the shared point's
+    /// x-coordinate.
+    ///
+    /// Unlike [`Secp256k1KeyPair::ecdh`], this only accepts the uncompressed
+    /// form: decoding a compressed P-256 point requires a modular square
+    /// root over the P-256 field, which `sgx_tcrypto` has no primitive for
+    /// and which isn't otherwise implemented here (see [`crate::bip32`]'s
+    /// module doc for the matching limitation on P-256 scalar arithmetic).
+    pub fn ecdh(&self, peer_public_key: &[u8]) -> EnclaveResult<[u8; 32]> {
+        if peer_public_key.len() == 33 {
+            return Err(EnclaveError::PublicKeyEncodingMismatch {
+                expected: PublicKeyEncoding::Uncompressed,
+                actual: PublicKeyEncoding::Compressed,
+            });
+        }
+        if peer_public_key.len() != 65 || peer_public_key[0] != 0x04 {
+            return Err(EnclaveError::InvalidParameter);
+        }
+        let mut peer = sgx_ec256_public_t::default();
+        peer.gx.copy_from_slice(&peer_public_key[1..33]);
+        peer.gy.copy_from_slice(&peer_public_key[33..65]);
+
+        let ecc_handle = SgxEccHandle::new();
+        ecc_handle.open()
+            .map_err(|e| EnclaveError::AgreementFailed(format!("ECC open failed: {:?}", e)))?;
+
+        let shared = ecc_handle.compute_shared_dhkey(&self.private_key, &peer)
+            .map_err(|e| EnclaveError::AgreementFailed(format!("ECDH failed: {:?}", e)))?;
+
+        sha256(&shared.s)
+    }
+
+    /// Verify an ECDSA P-256 signature against a raw SEC1 public key with no
+    /// matching private key on hand - for verifying a certificate or other
+    /// third-party signature rather than a key pair this enclave generated.
+    /// Only the uncompressed encoding (`04 || x || y`) is accepted; see
+    /// [`Self::ecdh`] for why compressed P-256 points aren't decoded here.
+    /// `signature` may be the compact `r || s` (64 bytes) or DER encoding,
+    /// matching [`Self::verify_encoded`].
+    pub fn verify_with_public_key(
+        public_key: &[u8],
+        data: &[u8],
+        signature: &[u8],
+    ) -> EnclaveResult<bool> {
+        if public_key.len() != 65 || public_key[0] != 0x04 {
+            return Err(EnclaveError::PublicKeyEncodingMismatch {
+                expected: PublicKeyEncoding::Uncompressed,
+                actual: PublicKeyEncoding::Compressed,
+            });
+        }
+        let mut pub_key = sgx_ec256_public_t::default();
+        pub_key.gx.copy_from_slice(&public_key[1..33]);
+        pub_key.gy.copy_from_slice(&public_key[33..65]);
+
+        let (r, s) = match signature.len() {
+            64 => {
+                let mut r = [0u8; 32];
+                let mut s = [0u8; 32];
+                r.copy_from_slice(&signature[..32]);
+                s.copy_from_slice(&signature[32..]);
+                (r, s)
+            }
+            _ => der_decode_signature(signature)?,
+        };
+        let mut sig = sgx_ec256_signature_t::default();
+        sig.x.copy_from_slice(&r);
+        sig.y.copy_from_slice(&s);
+
+        let hash = sha256(data)?;
+
+        let ecc_handle = SgxEccHandle::new();
+        ecc_handle.open()
+            .map_err(|e| EnclaveError::CryptoError(format!("ECC open failed: {:?}", e)))?;
+
+        ecc_handle.ecdsa_verify_slice(&hash, &pub_key, &sig)
+            .map_err(|e| EnclaveError::CryptoError(format!("ECDSA verify failed: {:?}", e)))
+    }
+}
+
+impl Drop for EcdsaKeyPair {
+    fn drop(&mut self) {
+        volatile_zero(&mut self.private_key.r);
+    }
+}
+
+/// secp256k1 key pair (Bitcoin/Ethereum curve).
+///
+/// `sgx_tcrypto`'s ECC engine only covers NIST P-256, so secp256k1 signing
+/// is implemented in pure Rust against [`crate::secp256k1`] instead of
+/// `SgxEccHandle`.
+pub struct Secp256k1KeyPair {
+    private_key: [u8; 32],
+    public_key: [u8; 65],
+}
+
+impl Secp256k1KeyPair {
+    /// Generate a new secp256k1 key pair using the enclave's RNG.
+    pub fn generate() -> EnclaveResult<Self> {
+        loop {
+            let candidate = random_bytes(32)?;
+            let mut private_key = [0u8; 32];
+            private_key.copy_from_slice(&candidate);
+            if let Ok(public_key) = secp256k1::public_key_from_private(&private_key) {
+                return Ok(Self { private_key, public_key });
+            }
+            // candidate was zero or >= the curve order; draw again.
+        }
+    }
+
+    /// Restore from private key bytes, recomputing the public key.
+    pub fn from_private_key(private_key: &[u8; 32]) -> EnclaveResult<Self> {
+        let public_key = secp256k1::public_key_from_private(private_key)?;
+        Ok(Self { private_key: *private_key, public_key })
+    }
+
+    /// Get the public key in uncompressed format (65 bytes: 04 || x || y).
+    pub fn public_key_bytes(&self) -> [u8; 65] {
+        self.public_key
+    }
+
+    /// Get the public key in the requested wire encoding.
+    pub fn public_key_bytes_encoded(&self, encoding: PublicKeyEncoding) -> Vec<u8> {
+        match encoding {
+            PublicKeyEncoding::Uncompressed => self.public_key.to_vec(),
+            PublicKeyEncoding::Compressed => {
+                let mut out = Vec::with_capacity(33);
+                out.push(if self.public_key[64] & 1 == 1 { 0x03 } else { 0x02 });
+                out.extend_from_slice(&self.public_key[1..33]);
+                out
+            }
+        }
+    }
+
+    /// Get the private key bytes.
+    pub fn private_key_bytes(&self) -> [u8; 32] {
+        self.private_key
+    }
+
+    /// Sign a 32-byte message hash, returning a recoverable `r || s || v`
+    /// signature normalized to low-S (EIP-2).
+    ///
+    /// The ephemeral nonce is derived deterministically per RFC 6979 from
+    /// the private key and message hash rather than drawn from the enclave
+    /// RNG, so signing the same message twice with the same key always
+    /// produces the same signature and a weak or stalled RNG can't lead to
+    /// nonce reuse. [`secp256k1::rfc6979_nonce`] only re-derives a candidate
+    /// until it lies in `1..n`; in the (astronomically unlikely) case where
+    /// the nonce yields `r == 0` or `s == 0`, [`secp256k1::sign_recoverable`]
+    /// returns an error and signing is not retried.
+    pub fn sign_recoverable(&self, message_hash: &[u8; 32]) -> EnclaveResult<[u8; 65]> {
+        let nonce = secp256k1::rfc6979_nonce(&self.private_key, message_hash)?;
+        let sig = secp256k1::sign_recoverable(&self.private_key, message_hash, &nonce)?;
+        Ok(sig.to_bytes())
+    }
+
+    /// Sign a 32-byte message hash, returning a compact `r || s` signature
+    /// (64 bytes, low-S normalized) with no recovery id — the form Neo and
+    /// most non-Ethereum secp256k1 verifiers expect.
+    pub fn sign(&self, message_hash: &[u8; 32]) -> EnclaveResult<[u8; 64]> {
+        let recoverable = self.sign_recoverable(message_hash)?;
+        let mut sig = [0u8; 64];
+        sig.copy_from_slice(&recoverable[..64]);
+        Ok(sig)
+    }
+
+    /// Verify a compact `r || s` signature against a SEC1 public key
+    /// (compressed 33-byte or uncompressed 65-byte).
+    pub fn verify(
+        public_key: &[u8],
+        message_hash: &[u8; 32],
+        signature: &[u8; 64],
+    ) -> EnclaveResult<bool> {
+        let point = secp256k1::decode_point(public_key)?;
+        let mut r_bytes = [0u8; 32];
+        let mut s_bytes = [0u8; 32];
+        r_bytes.copy_from_slice(&signature[..32]);
+        s_bytes.copy_from_slice(&signature[32..]);
+        let r = secp256k1::U256::from_be_bytes(&r_bytes);
+        let s = secp256k1::U256::from_be_bytes(&s_bytes);
+        Ok(secp256k1::verify(&point, message_hash, &r, &s))
+    }
+
+    /// Compute an ECDH shared secret with a peer's public key, in either
+    /// uncompressed (65-byte `04 || x || y`) or compressed (33-byte
+    /// `02/03 || x`) SEC1 form, returning SHA-256 of the shared point's
+    /// x-coordinate.
+    pub fn ecdh(&self, peer_public_key: &[u8]) -> EnclaveResult<[u8; 32]> {
+        let peer_point = secp256k1::decode_point(peer_public_key)?;
+        let scalar = secp256k1::U256::from_be_bytes(&self.private_key);
+        let shared_point = secp256k1::scalar_mul(&scalar, &peer_point);
+        if shared_point.infinity {
+            return Err(EnclaveError::PointAtInfinity);
+        }
+        sha256(&shared_point.x.to_be_bytes())
+    }
+}
+
+impl Drop for Secp256k1KeyPair {
+    fn drop(&mut self) {
+        volatile_zero(&mut self.private_key);
+    }
+}
+
+/// Encodes an ECDSA signature's `r`/`s` (each big-endian 32 bytes) as DER:
+/// `SEQUENCE { INTEGER r, INTEGER s }`, the format most on-chain tooling
+/// expects in place of this codebase's usual compact `r || s`. A leading
+/// `0x00` pad byte is inserted for either integer whose high bit is set, so
+/// it isn't misread as negative.
+pub fn der_encode_signature(r: &[u8; 32], s: &[u8; 32]) -> Vec<u8> {
+    fn encode_integer(bytes: &[u8; 32]) -> Vec<u8> {
+        let mut start = 0;
+        while start < 31 && bytes[start] == 0 {
+            start += 1;
+        }
+        let mut value = bytes[start..].to_vec();
+        if value[0] & 0x80 != 0 {
+            value.insert(0, 0x00);
+        }
+        let mut out = Vec::with_capacity(2 + value.len());
+        out.push(0x02); // INTEGER tag
+        out.push(value.len() as u8);
+        out.extend_from_slice(&value);
+        out
+    }
+
+    let r_der = encode_integer(r);
+    let s_der = encode_integer(s);
+    let mut out = Vec::with_capacity(2 + r_der.len() + s_der.len());
+    out.push(0x30); // SEQUENCE tag
+    out.push((r_der.len() + s_der.len()) as u8);
+    out.extend_from_slice(&r_der);
+    out.extend_from_slice(&s_der);
+    out
+}
+
+/// Decodes a DER-encoded ECDSA signature back to fixed-width 32-byte
+/// `r`/`s`. Both curves used here (P-256 and secp256k1) have 256-bit
+/// orders, so a valid signature's integers always fit within 32 bytes and
+/// the short-form DER length encoding (a single length byte, values < 128).
+pub fn der_decode_signature(der: &[u8]) -> EnclaveResult<([u8; 32], [u8; 32])> {
+    fn decode_integer(bytes: &[u8]) -> EnclaveResult<(&[u8], [u8; 32])> {
+        if bytes.len() < 2 || bytes[0] != 0x02 {
+            return Err(EnclaveError::SignatureEncodingMismatch {
+                expected: SignatureEncoding::Der,
+                actual: SignatureEncoding::Plain,
+            });
+        }
+        let len = bytes[1] as usize;
+        if len >= 0x80 || bytes.len() < 2 + len {
+            return Err(EnclaveError::InvalidParameter);
+        }
+        let mut value = &bytes[2..2 + len];
+        if value.len() > 1 && value[0] == 0 {
+            value = &value[1..];
+        }
+        if value.len() > 32 {
+            return Err(EnclaveError::InvalidParameter);
+        }
+        let mut out = [0u8; 32];
+        out[32 - value.len()..].copy_from_slice(value);
+        Ok((&bytes[2 + len..], out))
+    }
+
+    if der.len() < 2 || der[0] != 0x30 {
+        return Err(EnclaveError::SignatureEncodingMismatch {
+            expected: SignatureEncoding::Der,
+            actual: SignatureEncoding::Plain,
+        });
+    }
+    let seq_len = der[1] as usize;
+    if seq_len >= 0x80 || der.len() != 2 + seq_len {
+        return Err(EnclaveError::InvalidParameter);
+    }
+
+    let body = &der[2..2 + seq_len];
+    let (rest, r) = decode_integer(body)?;
+    let (rest, s) = decode_integer(rest)?;
+    if !rest.is_empty() {
+        return Err(EnclaveError::InvalidParameter);
+    }
+    Ok((r, s))
+}
+
+/// Recover the signing public key from a recoverable secp256k1 signature
+/// (Ethereum-style `ecrecover`).
+pub fn secp256k1_recover(
+    message_hash: &[u8; 32],
+    signature: &[u8; 65],
+) -> EnclaveResult<[u8; 65]> {
+    let sig = secp256k1::RecoverableSignature::from_bytes(signature)
+        .map_err(|_| EnclaveError::RecoveryFailed("malformed signature".to_string()))?;
+    secp256k1::recover_public_key(message_hash, &sig)
+        .map_err(|e| EnclaveError::RecoveryFailed(format!("{}", e)))
+}
+
+/// BIP-340 Schnorr key pair over secp256k1 (x-only public key, Taproot).
+///
+/// This coexists with [`Secp256k1KeyPair`]'s plain ECDSA path under the same
+/// curve; the two share no signing state and use distinct `KeyType` entries.
+pub struct SchnorrKeyPair {
+    private_key: [u8; 32],
+    public_key: [u8; 32],
+}
+
+impl SchnorrKeyPair {
+    /// Generate a new Schnorr key pair using the enclave's RNG.
+    pub fn generate() -> EnclaveResult<Self> {
+        loop {
+            let candidate = random_bytes(32)?;
+            let mut private_key = [0u8; 32];
+            private_key.copy_from_slice(&candidate);
+            if let Ok(public_key) = secp256k1::schnorr_public_key(&private_key) {
+                return Ok(Self { private_key, public_key });
+            }
+        }
+    }
+
+    /// Restore from private key bytes, recomputing the x-only public key.
+    pub fn from_private_key(private_key: &[u8; 32]) -> EnclaveResult<Self> {
+        let public_key = secp256k1::schnorr_public_key(private_key)?;
+        Ok(Self { private_key: *private_key, public_key })
+    }
+
+    /// Get the x-only public key (32 bytes, even-y representative).
+    pub fn public_key_bytes(&self) -> [u8; 32] {
+        self.public_key
+    }
+
+    /// Get the private key bytes.
+    pub fn private_key_bytes(&self) -> [u8; 32] {
+        self.private_key
+    }
+
+    /// Sign a 32-byte message with BIP-340 Schnorr, drawing fresh auxiliary
+    /// randomness from the enclave RNG for defense-in-depth against nonce
+    /// reuse (the nonce itself is still fully deterministic per BIP-340).
+    pub fn sign(&self, message: &[u8; 32]) -> EnclaveResult<[u8; 64]> {
+        let aux_bytes = random_bytes(32)?;
+        let mut aux_rand = [0u8; 32];
+        aux_rand.copy_from_slice(&aux_bytes);
+
+        let signature = secp256k1::sign_schnorr(&self.private_key, &aux_rand, message)?;
+        Ok(signature.to_bytes())
+    }
+}
+
+impl Drop for SchnorrKeyPair {
+    fn drop(&mut self) {
+        volatile_zero(&mut self.private_key);
+    }
+}
+
+/// Verify a BIP-340 Schnorr signature against a 32-byte x-only public key.
+pub fn schnorr_verify(
+    public_key: &[u8; 32],
+    message: &[u8; 32],
+    signature: &[u8; 64],
+) -> EnclaveResult<bool> {
+    let sig = secp256k1::SchnorrSignature::from_bytes(signature)?;
+    secp256k1::verify_schnorr(public_key, message, &sig)
+}
+
+// =============================================================================
+// ECIES: ECDH + KDF + AES-256-GCM hybrid encryption
+// =============================================================================
+
+/// Derives an AES-256-GCM key from an ECDH shared secret.
+fn ecies_derive_key(shared_secret: &[u8; 32]) -> EnclaveResult<[u8; 32]> {
+    let okm = hkdf_sha256(shared_secret, &[], b"ECIES-AES256-GCM", 32)?;
+    let mut key = [0u8; 32];
+    key.copy_from_slice(&okm);
+    Ok(key)
 }
 
-/// AES-256-GCM encryption.
+/// Encrypts under a key derived from `shared_secret`, returning
+/// `nonce(12) || ciphertext || tag(16)`.
+fn ecies_seal(shared_secret: &[u8; 32], plaintext: &[u8]) -> EnclaveResult<Vec<u8>> {
+    let key = ecies_derive_key(shared_secret)?;
+    let mut nonce = [0u8; 12];
+    nonce.copy_from_slice(&random_bytes(12)?);
+
+    let (ciphertext, tag) = AesGcm::encrypt(&key, &nonce, plaintext, &[])?;
+
+    let mut out = Vec::with_capacity(nonce.len() + ciphertext.len() + tag.len());
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&ciphertext);
+    out.extend_from_slice(&tag);
+    Ok(out)
+}
+
+/// Inverse of [`ecies_seal`].
+fn ecies_open(shared_secret: &[u8; 32], sealed: &[u8]) -> EnclaveResult<Vec<u8>> {
+    if sealed.len() < 12 + 16 {
+        return Err(EnclaveError::InvalidParameter);
+    }
+    let key = ecies_derive_key(shared_secret)?;
+
+    let mut nonce = [0u8; 12];
+    nonce.copy_from_slice(&sealed[..12]);
+    let mut tag = [0u8; 16];
+    tag.copy_from_slice(&sealed[sealed.len() - 16..]);
+    let ciphertext = &sealed[12..sealed.len() - 16];
+
+    AesGcm::decrypt(&key, &nonce, ciphertext, &[], &tag)
+}
+
+/// ECIES-encrypts `plaintext` under a recipient's secp256k1 public key: an
+/// ephemeral key pair is generated, a shared secret derived via ECDH, and
+/// the output is `ephemeral_pubkey(65) || nonce(12) || ciphertext || tag(16)`.
+/// Only the holder of the matching private key can decrypt it, so this is
+/// used to carry encrypted `ScriptRequest.input`/`ScriptResult.output`
+/// payloads that only the enclave should be able to read.
+pub fn secp256k1_ecies_encrypt(
+    recipient_public_key: &[u8; 65],
+    plaintext: &[u8],
+) -> EnclaveResult<Vec<u8>> {
+    let ephemeral = Secp256k1KeyPair::generate()?;
+    let shared_secret = ephemeral.ecdh(recipient_public_key)?;
+    let sealed = ecies_seal(&shared_secret, plaintext)?;
+
+    let mut out = Vec::with_capacity(65 + sealed.len());
+    out.extend_from_slice(&ephemeral.public_key_bytes());
+    out.extend_from_slice(&sealed);
+    Ok(out)
+}
+
+/// ECIES-decrypts a payload produced by [`secp256k1_ecies_encrypt`] using
+/// the recipient's secp256k1 private key.
+pub fn secp256k1_ecies_decrypt(
+    recipient_private_key: &[u8; 32],
+    ciphertext: &[u8],
+) -> EnclaveResult<Vec<u8>> {
+    if ciphertext.len() < 65 {
+        return Err(EnclaveError::InvalidParameter);
+    }
+    let mut ephemeral_public_key = [0u8; 65];
+    ephemeral_public_key.copy_from_slice(&ciphertext[..65]);
+
+    let recipient = Secp256k1KeyPair::from_private_key(recipient_private_key)?;
+    let shared_secret = recipient.ecdh(&ephemeral_public_key)?;
+    ecies_open(&shared_secret, &ciphertext[65..])
+}
+
+/// ECIES-encrypts `plaintext` under a recipient's P-256 public key (65
+/// bytes: `04 || x || y`). See [`secp256k1_ecies_encrypt`] for the format.
+pub fn p256_ecies_encrypt(recipient_public_key: &[u8], plaintext: &[u8]) -> EnclaveResult<Vec<u8>> {
+    let ephemeral = EcdsaKeyPair::generate()?;
+    let shared_secret = ephemeral.ecdh(recipient_public_key)?;
+    let sealed = ecies_seal(&shared_secret, plaintext)?;
+
+    let mut out = Vec::with_capacity(65 + sealed.len());
+    out.extend_from_slice(&ephemeral.public_key_bytes());
+    out.extend_from_slice(&sealed);
+    Ok(out)
+}
+
+/// ECIES-decrypts a payload produced by [`p256_ecies_encrypt`] using the
+/// recipient's P-256 private key.
+pub fn p256_ecies_decrypt(
+    recipient_private_key: &sgx_ec256_private_t,
+    ciphertext: &[u8],
+) -> EnclaveResult<Vec<u8>> {
+    if ciphertext.len() < 65 {
+        return Err(EnclaveError::InvalidParameter);
+    }
+    let recipient = EcdsaKeyPair {
+        private_key: *recipient_private_key,
+        public_key: sgx_ec256_public_t::default(),
+    };
+    let shared_secret = recipient.ecdh(&ciphertext[..65])?;
+    ecies_open(&shared_secret, &ciphertext[65..])
+}
+
+// --- Software AES-256 block cipher -----------------------------------
+//
+// `sgx_tcrypto`'s Rijndael API only covers 128-bit keys, so a genuine
+// AES-256 path (14 rounds, 8-word key schedule) has to be implemented
+// directly rather than handed off to the SDK. This section implements
+// the block cipher itself (FIPS-197); [`AesGcm`], [`AesCtr`], and
+// [`AesCbc`] below all build on [`aes256_encrypt_block`] /
+// [`aes256_decrypt_block`].
+
+const AES_SBOX: [u8; 256] = [
+    0x63, 0x7c, 0x77, 0x7b, 0xf2, 0x6b, 0x6f, 0xc5, 0x30, 0x01, 0x67, 0x2b, 0xfe, 0xd7, 0xab, 0x76,
+    0xca, 0x82, 0xc9, 0x7d, 0xfa, 0x59, 0x47, 0xf0, 0xad, 0xd4, 0xa2, 0xaf, 0x9c, 0xa4, 0x72, 0xc0,
+    0xb7, 0xfd, 0x93, 0x26, 0x36, 0x3f, 0xf7, 0xcc, 0x34, 0xa5, 0xe5, 0xf1, 0x71, 0xd8, 0x31, 0x15,
+    0x04, 0xc7, 0x23, 0xc3, 0x18, 0x96, 0x05, 0x9a, 0x07, 0x12, 0x80, 0xe2, 0xeb, 0x27, 0xb2, 0x75,
+    0x09, 0x83, 0x2c, 0x1a, 0x1b, 0x6e, 0x5a, 0xa0, 0x52, 0x3b, 0xd6, 0xb3, 0x29, 0xe3, 0x2f, 0x84,
+    0x53, 0xd1, 0x00, 0xed, 0x20, 0xfc, 0xb1, 0x5b, 0x6a, 0xcb, 0xbe, 0x39, 0x4a, 0x4c, 0x58, 0xcf,
+    0xd0, 0xef, 0xaa, 0xfb, 0x43, 0x4d, 0x33, 0x85, 0x45, 0xf9, 0x02, 0x7f, 0x50, 0x3c, 0x9f, 0xa8,
+    0x51, 0xa3, 0x40, 0x8f, 0x92, 0x9d, 0x38, 0xf5, 0xbc, 0xb6, 0xda, 0x21, 0x10, 0xff, 0xf3, 0xd2,
+    0xcd, 0x0c, 0x13, 0xec, 0x5f, 0x97, 0x44, 0x17, 0xc4, 0xa7, 0x7e, 0x3d, 0x64, 0x5d, 0x19, 0x73,
+    0x60, 0x81, 0x4f, 0xdc, 0x22, 0x2a, 0x90, 0x88, 0x46, 0xee, 0xb8, 0x14, 0xde, 0x5e, 0x0b, 0xdb,
+    0xe0, 0x32, 0x3a, 0x0a, 0x49, 0x06, 0x24, 0x5c, 0xc2, 0xd3, 0xac, 0x62, 0x91, 0x95, 0xe4, 0x79,
+    0xe7, 0xc8, 0x37, 0x6d, 0x8d, 0xd5, 0x4e, 0xa9, 0x6c, 0x56, 0xf4, 0xea, 0x65, 0x7a, 0xae, 0x08,
+    0xba, 0x78, 0x25, 0x2e, 0x1c, 0xa6, 0xb4, 0xc6, 0xe8, 0xdd, 0x74, 0x1f, 0x4b, 0xbd, 0x8b, 0x8a,
+    0x70, 0x3e, 0xb5, 0x66, 0x48, 0x03, 0xf6, 0x0e, 0x61, 0x35, 0x57, 0xb9, 0x86, 0xc1, 0x1d, 0x9e,
+    0xe1, 0xf8, 0x98, 0x11, 0x69, 0xd9, 0x8e, 0x94, 0x9b, 0x1e, 0x87, 0xe9, 0xce, 0x55, 0x28, 0xdf,
+    0x8c, 0xa1, 0x89, 0x0d, 0xbf, 0xe6, 0x42, 0x68, 0x41, 0x99, 0x2d, 0x0f, 0xb0, 0x54, 0xbb, 0x16,
+];
+
+/// Built from [`AES_SBOX`] on demand (cheap: 256 byte writes) rather than
+/// transcribed, so it can't drift out of sync with the forward table.
+fn aes_inv_sbox() -> [u8; 256] {
+    let mut inv = [0u8; 256];
+    for (i, &s) in AES_SBOX.iter().enumerate() {
+        inv[s as usize] = i as u8;
+    }
+    inv
+}
+
+/// x*2 in GF(2^8) with the AES reduction polynomial (x^8+x^4+x^3+x+1).
+fn xtime(a: u8) -> u8 {
+    let hi = a & 0x80;
+    let shifted = a << 1;
+    if hi != 0 { shifted ^ 0x1b } else { shifted }
+}
+
+/// Multiplication in GF(2^8), used by MixColumns/InvMixColumns.
+fn gmul(mut a: u8, mut b: u8) -> u8 {
+    let mut p = 0u8;
+    for _ in 0..8 {
+        if b & 1 != 0 {
+            p ^= a;
+        }
+        a = xtime(a);
+        b >>= 1;
+    }
+    p
+}
+
+fn rot_word(w: u32) -> u32 {
+    w.rotate_left(8)
+}
+
+fn sub_word(w: u32) -> u32 {
+    let b = w.to_be_bytes();
+    u32::from_be_bytes([
+        AES_SBOX[b[0] as usize],
+        AES_SBOX[b[1] as usize],
+        AES_SBOX[b[2] as usize],
+        AES_SBOX[b[3] as usize],
+    ])
+}
+
+/// AES-128 key schedule: expands a 16-byte key into 11 round keys
+/// (Nr=10, Nk=4) per FIPS-197 section 5.2. Needed alongside AES-256 because
+/// the Web3 secret-storage keystore format ([`crate::web3_keystore`]) is
+/// defined over AES-128-CTR, not AES-256.
+fn aes128_key_expansion(key: &[u8; 16]) -> [[u8; 16]; 11] {
+    const NK: usize = 4;
+    const NR: usize = 10;
+    let mut w = [0u32; 4 * (NR + 1)];
+
+    for i in 0..NK {
+        w[i] = u32::from_be_bytes([key[4 * i], key[4 * i + 1], key[4 * i + 2], key[4 * i + 3]]);
+    }
+
+    let mut rcon = 1u8;
+    for i in NK..w.len() {
+        let mut temp = w[i - 1];
+        if i % NK == 0 {
+            temp = sub_word(rot_word(temp)) ^ ((rcon as u32) << 24);
+            rcon = xtime(rcon);
+        }
+        w[i] = w[i - NK] ^ temp;
+    }
+
+    let mut round_keys = [[0u8; 16]; 11];
+    for (round, key_bytes) in round_keys.iter_mut().enumerate() {
+        for j in 0..4 {
+            key_bytes[4 * j..4 * j + 4].copy_from_slice(&w[4 * round + j].to_be_bytes());
+        }
+    }
+    round_keys
+}
+
+fn aes128_encrypt_block(round_keys: &[[u8; 16]; 11], input: &[u8; 16]) -> [u8; 16] {
+    let mut state = *input;
+    add_round_key(&mut state, &round_keys[0]);
+    for round_key in &round_keys[1..10] {
+        sub_bytes(&mut state);
+        shift_rows(&mut state);
+        mix_columns(&mut state);
+        add_round_key(&mut state, round_key);
+    }
+    sub_bytes(&mut state);
+    shift_rows(&mut state);
+    add_round_key(&mut state, &round_keys[10]);
+    state
+}
+
+/// AES-128-CTR keystream XOR, per NIST SP 800-38A. CTR mode is its own
+/// inverse, so this serves both encryption and decryption.
+pub(crate) fn aes128_ctr_xor(key: &[u8; 16], counter_block: &[u8; 16], data: &[u8]) -> Vec<u8> {
+    let round_keys = aes128_key_expansion(key);
+    let mut counter = *counter_block;
+    let mut out = Vec::with_capacity(data.len());
+    for chunk in data.chunks(16) {
+        let keystream = aes128_encrypt_block(&round_keys, &counter);
+        for (b, k) in chunk.iter().zip(keystream.iter()) {
+            out.push(b ^ k);
+        }
+        inc32(&mut counter);
+    }
+    out
+}
+
+/// AES-256 key schedule: expands a 32-byte key into 15 round keys
+/// (Nr=14, Nk=8) per FIPS-197 section 5.2.
+fn aes256_key_expansion(key: &[u8; 32]) -> [[u8; 16]; 15] {
+    const NK: usize = 8;
+    const NR: usize = 14;
+    let mut w = [0u32; 4 * (NR + 1)];
+
+    for i in 0..NK {
+        w[i] = u32::from_be_bytes([key[4 * i], key[4 * i + 1], key[4 * i + 2], key[4 * i + 3]]);
+    }
+
+    let mut rcon = 1u8;
+    for i in NK..w.len() {
+        let mut temp = w[i - 1];
+        if i % NK == 0 {
+            temp = sub_word(rot_word(temp)) ^ ((rcon as u32) << 24);
+            rcon = xtime(rcon);
+        } else if NK > 6 && i % NK == 4 {
+            temp = sub_word(temp);
+        }
+        w[i] = w[i - NK] ^ temp;
+    }
+
+    let mut round_keys = [[0u8; 16]; 15];
+    for (round, key_bytes) in round_keys.iter_mut().enumerate() {
+        for j in 0..4 {
+            key_bytes[4 * j..4 * j + 4].copy_from_slice(&w[4 * round + j].to_be_bytes());
+        }
+    }
+    round_keys
+}
+
+fn add_round_key(state: &mut [u8; 16], round_key: &[u8; 16]) {
+    for i in 0..16 {
+        state[i] ^= round_key[i];
+    }
+}
+
+fn sub_bytes(state: &mut [u8; 16]) {
+    for b in state.iter_mut() {
+        *b = AES_SBOX[*b as usize];
+    }
+}
+
+fn inv_sub_bytes(state: &mut [u8; 16], inv_sbox: &[u8; 256]) {
+    for b in state.iter_mut() {
+        *b = inv_sbox[*b as usize];
+    }
+}
+
+/// State is column-major: byte `r + 4*c` is row `r`, column `c`.
+fn shift_rows(state: &mut [u8; 16]) {
+    let s = *state;
+    for r in 1..4 {
+        for c in 0..4 {
+            state[r + 4 * c] = s[r + 4 * ((c + r) % 4)];
+        }
+    }
+}
+
+fn inv_shift_rows(state: &mut [u8; 16]) {
+    let s = *state;
+    for r in 1..4 {
+        for c in 0..4 {
+            state[r + 4 * c] = s[r + 4 * ((c + 4 - r) % 4)];
+        }
+    }
+}
+
+fn mix_columns(state: &mut [u8; 16]) {
+    for c in 0..4 {
+        let a = [state[4 * c], state[4 * c + 1], state[4 * c + 2], state[4 * c + 3]];
+        state[4 * c] = gmul(a[0], 2) ^ gmul(a[1], 3) ^ a[2] ^ a[3];
+        state[4 * c + 1] = a[0] ^ gmul(a[1], 2) ^ gmul(a[2], 3) ^ a[3];
+        state[4 * c + 2] = a[0] ^ a[1] ^ gmul(a[2], 2) ^ gmul(a[3], 3);
+        state[4 * c + 3] = gmul(a[0], 3) ^ a[1] ^ a[2] ^ gmul(a[3], 2);
+    }
+}
+
+fn inv_mix_columns(state: &mut [u8; 16]) {
+    for c in 0..4 {
+        let a = [state[4 * c], state[4 * c + 1], state[4 * c + 2], state[4 * c + 3]];
+        state[4 * c] = gmul(a[0], 14) ^ gmul(a[1], 11) ^ gmul(a[2], 13) ^ gmul(a[3], 9);
+        state[4 * c + 1] = gmul(a[0], 9) ^ gmul(a[1], 14) ^ gmul(a[2], 11) ^ gmul(a[3], 13);
+        state[4 * c + 2] = gmul(a[0], 13) ^ gmul(a[1], 9) ^ gmul(a[2], 14) ^ gmul(a[3], 11);
+        state[4 * c + 3] = gmul(a[0], 11) ^ gmul(a[1], 13) ^ gmul(a[2], 9) ^ gmul(a[3], 14);
+    }
+}
+
+fn aes256_encrypt_block(round_keys: &[[u8; 16]; 15], input: &[u8; 16]) -> [u8; 16] {
+    let mut state = *input;
+    add_round_key(&mut state, &round_keys[0]);
+    for round_key in &round_keys[1..14] {
+        sub_bytes(&mut state);
+        shift_rows(&mut state);
+        mix_columns(&mut state);
+        add_round_key(&mut state, round_key);
+    }
+    sub_bytes(&mut state);
+    shift_rows(&mut state);
+    add_round_key(&mut state, &round_keys[14]);
+    state
+}
+
+fn aes256_decrypt_block(round_keys: &[[u8; 16]; 15], input: &[u8; 16]) -> [u8; 16] {
+    let inv_sbox = aes_inv_sbox();
+    let mut state = *input;
+    add_round_key(&mut state, &round_keys[14]);
+    for round_key in round_keys[1..14].iter().rev() {
+        inv_shift_rows(&mut state);
+        inv_sub_bytes(&mut state, &inv_sbox);
+        add_round_key(&mut state, round_key);
+        inv_mix_columns(&mut state);
+    }
+    inv_shift_rows(&mut state);
+    inv_sub_bytes(&mut state, &inv_sbox);
+    add_round_key(&mut state, &round_keys[0]);
+    state
+}
+
+/// Increments only the last 32 bits of a 128-bit counter block, per
+/// NIST SP 800-38A's `inc32`.
+fn inc32(block: &mut [u8; 16]) {
+    let counter = u32::from_be_bytes([block[12], block[13], block[14], block[15]]);
+    block[12..16].copy_from_slice(&counter.wrapping_add(1).to_be_bytes());
+}
+
+/// AES-256 in counter mode: encrypts successive values of `counter_block`
+/// (advanced via [`inc32`] between blocks) and XORs the keystream with
+/// `data`. Identical operation for encryption and decryption.
+fn aes256_ctr_xor(round_keys: &[[u8; 16]; 15], counter_block: &[u8; 16], data: &[u8]) -> Vec<u8> {
+    let mut counter = *counter_block;
+    let mut out = Vec::with_capacity(data.len());
+    for chunk in data.chunks(16) {
+        let keystream = aes256_encrypt_block(round_keys, &counter);
+        for (b, k) in chunk.iter().zip(keystream.iter()) {
+            out.push(b ^ k);
+        }
+        inc32(&mut counter);
+    }
+    out
+}
+
+/// GF(2^128) multiplication for GHASH, per NIST SP 800-38D algorithm 1
+/// (bit 0 of `x`/`y` is the MSB of the first byte; reduction polynomial
+/// `R = 0xe1 || 0^120`).
+fn ghash_mul(x: &[u8; 16], y: &[u8; 16]) -> [u8; 16] {
+    let mut z = [0u8; 16];
+    let mut v = *y;
+    for i in 0..16 {
+        for bit in 0..8 {
+            if (x[i] >> (7 - bit)) & 1 == 1 {
+                for k in 0..16 {
+                    z[k] ^= v[k];
+                }
+            }
+            let lsb_set = v[15] & 1 != 0;
+            for k in (1..16).rev() {
+                v[k] = (v[k] >> 1) | ((v[k - 1] & 1) << 7);
+            }
+            v[0] >>= 1;
+            if lsb_set {
+                v[0] ^= 0xe1;
+            }
+        }
+    }
+    z
+}
+
+fn ghash(h: &[u8; 16], data: &[u8]) -> [u8; 16] {
+    let mut y = [0u8; 16];
+    for chunk in data.chunks(16) {
+        let mut block = [0u8; 16];
+        block[..chunk.len()].copy_from_slice(chunk);
+        for i in 0..16 {
+            y[i] ^= block[i];
+        }
+        y = ghash_mul(&y, h);
+    }
+    y
+}
+
+/// Computes GHASH_H(`AAD || pad || C || pad || len(AAD) || len(C)`) (each
+/// section zero-padded to a 16-byte boundary independently, lengths in
+/// bits as big-endian u64s) per SP 800-38D section 7.1.
+fn ghash_with_key(h: &[u8; 16], aad: &[u8], ciphertext: &[u8]) -> [u8; 16] {
+    let aad_pad = (16 - aad.len() % 16) % 16;
+    let ct_pad = (16 - ciphertext.len() % 16) % 16;
+    let mut buf = Vec::with_capacity(aad.len() + aad_pad + ciphertext.len() + ct_pad + 16);
+    buf.extend_from_slice(aad);
+    buf.resize(buf.len() + aad_pad, 0);
+    buf.extend_from_slice(ciphertext);
+    buf.resize(buf.len() + ct_pad, 0);
+    buf.extend_from_slice(&((aad.len() as u64) * 8).to_be_bytes());
+    buf.extend_from_slice(&((ciphertext.len() as u64) * 8).to_be_bytes());
+    ghash(h, &buf)
+}
+
+fn aes256_gcm_seal(key: &[u8; 32], iv: &[u8; 12], plaintext: &[u8], aad: &[u8]) -> (Vec<u8>, [u8; 16]) {
+    let round_keys = aes256_key_expansion(key);
+    let h = aes256_encrypt_block(&round_keys, &[0u8; 16]);
+
+    let mut j0 = [0u8; 16];
+    j0[..12].copy_from_slice(iv);
+    j0[15] = 1;
+
+    let mut first_counter = j0;
+    inc32(&mut first_counter);
+    let ciphertext = aes256_ctr_xor(&round_keys, &first_counter, plaintext);
+
+    let s = ghash_with_key(&h, aad, &ciphertext);
+    let tag_mask = aes256_encrypt_block(&round_keys, &j0);
+    let mut tag = [0u8; 16];
+    for i in 0..16 {
+        tag[i] = s[i] ^ tag_mask[i];
+    }
+    (ciphertext, tag)
+}
+
+/// AES-256-GCM encryption (software implementation - see the module-level
+/// comment for why `sgx_tcrypto`'s 128-bit-only Rijndael API can't be used
+/// directly).
 pub struct AesGcm;
 
 impl AesGcm {
@@ -163,24 +1037,7 @@ impl AesGcm {
         plaintext: &[u8],
         aad: &[u8],
     ) -> EnclaveResult<(Vec<u8>, [u8; 16])> {
-        // SGX uses 128-bit key for its GCM API, use first 16 bytes
-        // In production, would use full AES-256
-        let mut aes_key = sgx_aes_gcm_128bit_key_t::default();
-        aes_key.copy_from_slice(&key[..16]);
-
-        let mut ciphertext = vec![0u8; plaintext.len()];
-        let mut tag = sgx_aes_gcm_128bit_tag_t::default();
-
-        rsgx_rijndael128GCM_encrypt(
-            &aes_key,
-            plaintext,
-            iv,
-            aad,
-            &mut ciphertext,
-            &mut tag,
-        ).map_err(|e| EnclaveError::CryptoError(format!("AES-GCM encrypt failed: {:?}", e)))?;
-
-        Ok((ciphertext, tag))
+        Ok(aes256_gcm_seal(key, iv, plaintext, aad))
     }
 
     /// Decrypt data using AES-256-GCM.
@@ -201,24 +1058,184 @@ impl AesGcm {
         aad: &[u8],
         tag: &[u8; 16],
     ) -> EnclaveResult<Vec<u8>> {
-        let mut aes_key = sgx_aes_gcm_128bit_key_t::default();
-        aes_key.copy_from_slice(&key[..16]);
+        let round_keys = aes256_key_expansion(key);
+        let h = aes256_encrypt_block(&round_keys, &[0u8; 16]);
 
-        let mut aes_tag = sgx_aes_gcm_128bit_tag_t::default();
-        aes_tag.copy_from_slice(tag);
+        let mut j0 = [0u8; 16];
+        j0[..12].copy_from_slice(iv);
+        j0[15] = 1;
 
-        let mut plaintext = vec![0u8; ciphertext.len()];
+        let s = ghash_with_key(&h, aad, ciphertext);
+        let tag_mask = aes256_encrypt_block(&round_keys, &j0);
+        let mut expected_tag = [0u8; 16];
+        for i in 0..16 {
+            expected_tag[i] = s[i] ^ tag_mask[i];
+        }
 
-        rsgx_rijndael128GCM_decrypt(
-            &aes_key,
-            ciphertext,
-            iv,
-            aad,
-            &aes_tag,
-            &mut plaintext,
-        ).map_err(|e| EnclaveError::CryptoError(format!("AES-GCM decrypt failed: {:?}", e)))?;
+        if !ct_eq(&expected_tag, tag) {
+            return Err(EnclaveError::CryptoError("AES-GCM authentication failed".to_string()));
+        }
 
-        Ok(plaintext)
+        let mut first_counter = j0;
+        inc32(&mut first_counter);
+        Ok(aes256_ctr_xor(&round_keys, &first_counter, ciphertext))
+    }
+}
+
+/// AES-256-CTR: unauthenticated stream cipher built on [`aes256_ctr_xor`].
+/// `iv` is the full 128-bit initial counter block (caller-managed - unlike
+/// [`AesGcm`], there is no implicit `|| 0^31 || 1` construction).
+pub struct AesCtr;
+
+impl AesCtr {
+    /// Encrypt (equivalently, decrypt) `data` under CTR mode.
+    pub fn encrypt(key: &[u8; 32], iv: &[u8; 16], data: &[u8]) -> EnclaveResult<Vec<u8>> {
+        let round_keys = aes256_key_expansion(key);
+        Ok(aes256_ctr_xor(&round_keys, iv, data))
+    }
+
+    /// CTR decryption is the same keystream XOR as encryption.
+    pub fn decrypt(key: &[u8; 32], iv: &[u8; 16], data: &[u8]) -> EnclaveResult<Vec<u8>> {
+        Self::encrypt(key, iv, data)
+    }
+}
+
+/// AES-256-CBC with PKCS#7 padding: unauthenticated, 16-byte IV.
+pub struct AesCbc;
+
+impl AesCbc {
+    /// Pad `plaintext` to a block boundary (PKCS#7) and encrypt under CBC.
+    pub fn encrypt(key: &[u8; 32], iv: &[u8; 16], plaintext: &[u8]) -> EnclaveResult<Vec<u8>> {
+        let round_keys = aes256_key_expansion(key);
+
+        let pad_len = 16 - (plaintext.len() % 16);
+        let mut padded = Vec::with_capacity(plaintext.len() + pad_len);
+        padded.extend_from_slice(plaintext);
+        padded.resize(padded.len() + pad_len, pad_len as u8);
+
+        let mut prev = *iv;
+        let mut out = Vec::with_capacity(padded.len());
+        for chunk in padded.chunks(16) {
+            let mut block = [0u8; 16];
+            block.copy_from_slice(chunk);
+            add_round_key(&mut block, &prev);
+            let ciphertext_block = aes256_encrypt_block(&round_keys, &block);
+            out.extend_from_slice(&ciphertext_block);
+            prev = ciphertext_block;
+        }
+        Ok(out)
+    }
+
+    /// Decrypt a CBC ciphertext and strip its PKCS#7 padding.
+    pub fn decrypt(key: &[u8; 32], iv: &[u8; 16], ciphertext: &[u8]) -> EnclaveResult<Vec<u8>> {
+        if ciphertext.is_empty() || ciphertext.len() % 16 != 0 {
+            return Err(EnclaveError::InvalidParameter);
+        }
+        let round_keys = aes256_key_expansion(key);
+
+        let mut prev = *iv;
+        let mut out = Vec::with_capacity(ciphertext.len());
+        for chunk in ciphertext.chunks(16) {
+            let mut block = [0u8; 16];
+            block.copy_from_slice(chunk);
+            let mut plaintext_block = aes256_decrypt_block(&round_keys, &block);
+            add_round_key(&mut plaintext_block, &prev);
+            out.extend_from_slice(&plaintext_block);
+            prev = block;
+        }
+
+        let pad_len = *out.last().ok_or(EnclaveError::InvalidParameter)? as usize;
+        if pad_len == 0 || pad_len > 16 || pad_len > out.len() {
+            return Err(EnclaveError::CryptoError("invalid PKCS#7 padding".to_string()));
+        }
+        if out[out.len() - pad_len..].iter().any(|&b| b as usize != pad_len) {
+            return Err(EnclaveError::CryptoError("invalid PKCS#7 padding".to_string()));
+        }
+        out.truncate(out.len() - pad_len);
+        Ok(out)
+    }
+}
+
+/// Symmetric cipher mode selector for [`aes_encrypt`]/[`aes_decrypt`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AesMode {
+    /// AES-256-GCM: authenticated, 12-byte IV. The returned/expected
+    /// buffer is `ciphertext || 16-byte tag`.
+    Gcm,
+    /// AES-256-CTR: unauthenticated, 16-byte initial counter block.
+    Ctr,
+    /// AES-256-CBC with PKCS#7 padding: unauthenticated, 16-byte IV.
+    Cbc,
+}
+
+/// Single entry point over [`AesGcm`]/[`AesCtr`]/[`AesCbc`] for callers
+/// that pick their cipher mode at runtime. `aad` is only meaningful for
+/// [`AesMode::Gcm`]; it must be empty for the unauthenticated modes.
+pub fn aes_encrypt(
+    mode: AesMode,
+    key: &[u8; 32],
+    iv: &[u8],
+    plaintext: &[u8],
+    aad: &[u8],
+) -> EnclaveResult<Vec<u8>> {
+    match mode {
+        AesMode::Gcm => {
+            let iv: &[u8; 12] = iv.try_into().map_err(|_| EnclaveError::InvalidParameter)?;
+            let (mut ciphertext, tag) = AesGcm::encrypt(key, iv, plaintext, aad)?;
+            ciphertext.extend_from_slice(&tag);
+            Ok(ciphertext)
+        }
+        AesMode::Ctr => {
+            if !aad.is_empty() {
+                return Err(EnclaveError::InvalidParameter);
+            }
+            let iv: &[u8; 16] = iv.try_into().map_err(|_| EnclaveError::InvalidParameter)?;
+            AesCtr::encrypt(key, iv, plaintext)
+        }
+        AesMode::Cbc => {
+            if !aad.is_empty() {
+                return Err(EnclaveError::InvalidParameter);
+            }
+            let iv: &[u8; 16] = iv.try_into().map_err(|_| EnclaveError::InvalidParameter)?;
+            AesCbc::encrypt(key, iv, plaintext)
+        }
+    }
+}
+
+/// Inverse of [`aes_encrypt`] - see its docs for the per-mode `iv`/`aad`
+/// conventions.
+pub fn aes_decrypt(
+    mode: AesMode,
+    key: &[u8; 32],
+    iv: &[u8],
+    data: &[u8],
+    aad: &[u8],
+) -> EnclaveResult<Vec<u8>> {
+    match mode {
+        AesMode::Gcm => {
+            if data.len() < 16 {
+                return Err(EnclaveError::InvalidParameter);
+            }
+            let (ciphertext, tag) = data.split_at(data.len() - 16);
+            let iv: &[u8; 12] = iv.try_into().map_err(|_| EnclaveError::InvalidParameter)?;
+            let mut tag_arr = [0u8; 16];
+            tag_arr.copy_from_slice(tag);
+            AesGcm::decrypt(key, iv, ciphertext, aad, &tag_arr)
+        }
+        AesMode::Ctr => {
+            if !aad.is_empty() {
+                return Err(EnclaveError::InvalidParameter);
+            }
+            let iv: &[u8; 16] = iv.try_into().map_err(|_| EnclaveError::InvalidParameter)?;
+            AesCtr::decrypt(key, iv, data)
+        }
+        AesMode::Cbc => {
+            if !aad.is_empty() {
+                return Err(EnclaveError::InvalidParameter);
+            }
+            let iv: &[u8; 16] = iv.try_into().map_err(|_| EnclaveError::InvalidParameter)?;
+            AesCbc::decrypt(key, iv, data)
+        }
     }
 }
 
@@ -252,38 +1269,277 @@ pub fn generate_key() -> EnclaveResult<[u8; 32]> {
     Ok(key)
 }
 
-/// Derive a key using HKDF (HMAC-based Key Derivation Function).
-/// Simplified implementation using SHA-256.
+/// Compares two byte slices in data-independent time: every byte pair is
+/// inspected regardless of earlier mismatches, so the comparison can't leak
+/// how many leading bytes matched through a timing side channel. Use this
+/// instead of `==`/`!=` for authentication tags, MACs, and other secrets
+/// (e.g. [`AesGcm::decrypt`]'s tag check and [`crate::web3_keystore`]'s MAC
+/// check) - never for data that's already public, where the early-exit
+/// comparison the compiler generates for `==` is strictly better.
+///
+/// A length mismatch returns `false` immediately: tag/MAC sizes are fixed
+/// by the algorithm at every call site in this crate, so the length itself
+/// is never the secret being protected.
+pub fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut diff = 0u8;
+    for i in 0..a.len() {
+        diff |= a[i] ^ b[i];
+    }
+    diff == 0
+}
+
+/// Overwrites `buf` with zeros through a volatile write, so the compiler
+/// can't prove the write is dead and elide it even though `buf` is about to
+/// be freed or go out of scope - a plain `for b in buf { *b = 0 }` right
+/// before a drop is exactly the kind of store a dead-store-elimination pass
+/// is allowed to remove.
+fn volatile_zero(buf: &mut [u8]) {
+    for byte in buf.iter_mut() {
+        unsafe { std::ptr::write_volatile(byte, 0) };
+    }
+    std::sync::atomic::compiler_fence(std::sync::atomic::Ordering::SeqCst);
+}
+
+/// Zeroes a secret byte buffer when it is dropped.
+///
+/// [`random_bytes`]/[`generate_key`] return plain `Vec<u8>`/`[u8; 32]`
+/// values, which can't implement `Drop` themselves (the orphan rule - they
+/// aren't types this crate defines), so callers that need the zero-on-free
+/// guarantee for that raw key material wrap it in `Zeroizing` instead:
+///
+/// ```ignore
+/// let key = Zeroizing::new(generate_key()?);
+/// // `key` derefs to `[u8; 32]`; it is overwritten with zeros when it
+/// // goes out of scope, success or error.
+/// ```
+pub struct Zeroizing<T: AsMut<[u8]>>(T);
+
+impl<T: AsMut<[u8]>> Zeroizing<T> {
+    pub fn new(inner: T) -> Self {
+        Self(inner)
+    }
+}
+
+impl<T: AsMut<[u8]>> std::ops::Deref for Zeroizing<T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: AsMut<[u8]>> std::ops::DerefMut for Zeroizing<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: AsMut<[u8]>> Drop for Zeroizing<T> {
+    fn drop(&mut self) {
+        volatile_zero(self.0.as_mut());
+    }
+}
+
+/// SHA-512 round constants (fractional parts of the cube roots of the first
+/// 80 primes).
+const SHA512_K: [u64; 80] = [
+    0x428a2f98d728ae22, 0x7137449123ef65cd, 0xb5c0fbcfec4d3b2f, 0xe9b5dba58189dbbc,
+    0x3956c25bf348b538, 0x59f111f1b605d019, 0x923f82a4af194f9b, 0xab1c5ed5da6d8118,
+    0xd807aa98a3030242, 0x12835b0145706fbe, 0x243185be4ee4b28c, 0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f, 0x80deb1fe3b1696b1, 0x9bdc06a725c71235, 0xc19bf174cf692694,
+    0xe49b69c19ef14ad2, 0xefbe4786384f25e3, 0x0fc19dc68b8cd5b5, 0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275, 0x4a7484aa6ea6e483, 0x5cb0a9dcbd41fbd4, 0x76f988da831153b5,
+    0x983e5152ee66dfab, 0xa831c66d2db43210, 0xb00327c898fb213f, 0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2, 0xd5a79147930aa725, 0x06ca6351e003826f, 0x142929670a0e6e70,
+    0x27b70a8546d22ffc, 0x2e1b21385c26c926, 0x4d2c6dfc5ac42aed, 0x53380d139d95b3df,
+    0x650a73548baf63de, 0x766a0abb3c77b2a8, 0x81c2c92e47edaee6, 0x92722c851482353b,
+    0xa2bfe8a14cf10364, 0xa81a664bbc423001, 0xc24b8b70d0f89791, 0xc76c51a30654be30,
+    0xd192e819d6ef5218, 0xd69906245565a910, 0xf40e35855771202a, 0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8, 0x1e376c085141ab53, 0x2748774cdf8eeb99, 0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63, 0x4ed8aa4ae3418acb, 0x5b9cca4f7763e373, 0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc, 0x78a5636f43172f60, 0x84c87814a1f0ab72, 0x8cc702081a6439ec,
+    0x90befffa23631e28, 0xa4506cebde82bde9, 0xbef9a3f7b2c67915, 0xc67178f2e372532b,
+    0xca273eceea26619c, 0xd186b8c721c0c207, 0xeada7dd6cde0eb1e, 0xf57d4f7fee6ed178,
+    0x06f067aa72176fba, 0x0a637dc5a2c898a6, 0x113f9804bef90dae, 0x1b710b35131c471b,
+    0x28db77f523047d84, 0x32caab7b40c72493, 0x3c9ebe0a15c9bebc, 0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6, 0x597f299cfc657e2a, 0x5fcb6fab3ad6faec, 0x6c44198c4a475817,
+];
+
+/// Compute SHA-512 hash.
+///
+/// `sgx_tcrypto` only exposes SHA-256, so SHA-512 (needed for HMAC-SHA512 in
+/// BIP-32 key derivation) is implemented here directly per FIPS 180-4.
+pub fn sha512(data: &[u8]) -> [u8; 64] {
+    let mut h: [u64; 8] = [
+        0x6a09e667f3bcc908, 0xbb67ae8584caa73b, 0x3c6ef372fe94f82b, 0xa54ff53a5f1d36f1,
+        0x510e527fade682d1, 0x9b05688c2b3e6c1f, 0x1f83d9abfb41bd6b, 0x5be0cd19137e2179,
+    ];
+
+    // Pad: message || 0x80 || zeros || 128-bit big-endian bit length, to a
+    // multiple of the 128-byte block size.
+    let bit_len = (data.len() as u128) * 8;
+    let mut padded = data.to_vec();
+    padded.push(0x80);
+    while padded.len() % 128 != 112 {
+        padded.push(0);
+    }
+    padded.extend_from_slice(&bit_len.to_be_bytes());
+
+    for block in padded.chunks(128) {
+        let mut w = [0u64; 80];
+        for i in 0..16 {
+            let mut chunk = [0u8; 8];
+            chunk.copy_from_slice(&block[i * 8..i * 8 + 8]);
+            w[i] = u64::from_be_bytes(chunk);
+        }
+        for i in 16..80 {
+            let s0 = w[i - 15].rotate_right(1) ^ w[i - 15].rotate_right(8) ^ (w[i - 15] >> 7);
+            let s1 = w[i - 2].rotate_right(19) ^ w[i - 2].rotate_right(61) ^ (w[i - 2] >> 6);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+
+        let (mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh) =
+            (h[0], h[1], h[2], h[3], h[4], h[5], h[6], h[7]);
+
+        for i in 0..80 {
+            let s1 = e.rotate_right(14) ^ e.rotate_right(18) ^ e.rotate_right(41);
+            let ch = (e & f) ^ ((!e) & g);
+            let temp1 = hh
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(SHA512_K[i])
+                .wrapping_add(w[i]);
+            let s0 = a.rotate_right(28) ^ a.rotate_right(34) ^ a.rotate_right(39);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let temp2 = s0.wrapping_add(maj);
+
+            hh = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(temp1);
+            d = c;
+            c = b;
+            b = a;
+            a = temp1.wrapping_add(temp2);
+        }
+
+        h[0] = h[0].wrapping_add(a);
+        h[1] = h[1].wrapping_add(b);
+        h[2] = h[2].wrapping_add(c);
+        h[3] = h[3].wrapping_add(d);
+        h[4] = h[4].wrapping_add(e);
+        h[5] = h[5].wrapping_add(f);
+        h[6] = h[6].wrapping_add(g);
+        h[7] = h[7].wrapping_add(hh);
+    }
+
+    let mut out = [0u8; 64];
+    for i in 0..8 {
+        out[i * 8..i * 8 + 8].copy_from_slice(&h[i].to_be_bytes());
+    }
+    out
+}
+
+/// HMAC-SHA512, per RFC 2104. Used by [`crate::bip32`] for BIP-32 child key
+/// derivation (`I = HMAC-SHA512(chain_code, data)`).
+pub fn hmac_sha512(key: &[u8], message: &[u8]) -> [u8; 64] {
+    const BLOCK_SIZE: usize = 128;
+
+    let mut key_block = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        key_block[..64].copy_from_slice(&sha512(key));
+    } else {
+        key_block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut ipad = [0x36u8; BLOCK_SIZE];
+    let mut opad = [0x5cu8; BLOCK_SIZE];
+    for i in 0..BLOCK_SIZE {
+        ipad[i] ^= key_block[i];
+        opad[i] ^= key_block[i];
+    }
+
+    let mut inner_input = Vec::with_capacity(BLOCK_SIZE + message.len());
+    inner_input.extend_from_slice(&ipad);
+    inner_input.extend_from_slice(message);
+    let inner_hash = sha512(&inner_input);
+
+    let mut outer_input = Vec::with_capacity(BLOCK_SIZE + 64);
+    outer_input.extend_from_slice(&opad);
+    outer_input.extend_from_slice(&inner_hash);
+    sha512(&outer_input)
+}
+
+/// HMAC-SHA256, per RFC 2104. Used by [`crate::secp256k1`] for RFC 6979
+/// deterministic nonce generation.
+pub fn hmac_sha256(key: &[u8], message: &[u8]) -> EnclaveResult<[u8; 32]> {
+    const BLOCK_SIZE: usize = 64;
+
+    let mut key_block = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        key_block[..32].copy_from_slice(&sha256(key)?);
+    } else {
+        key_block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut ipad = [0x36u8; BLOCK_SIZE];
+    let mut opad = [0x5cu8; BLOCK_SIZE];
+    for i in 0..BLOCK_SIZE {
+        ipad[i] ^= key_block[i];
+        opad[i] ^= key_block[i];
+    }
+
+    let mut ctx = Sha256Context::new()?;
+    ctx.update(&ipad)?;
+    ctx.update(message)?;
+    let inner_hash = ctx.finalize()?;
+
+    let mut ctx = Sha256Context::new()?;
+    ctx.update(&opad)?;
+    ctx.update(&inner_hash)?;
+    ctx.finalize()
+}
+
+/// Derive key material using HKDF-SHA256, per RFC 5869: Extract then
+/// Expand, both built on [`hmac_sha256`] (not a plain SHA-256 hash - an
+/// unkeyed hash over `salt || ikm || info` is not a PRF and isn't
+/// interoperable with any other HKDF implementation).
 pub fn hkdf_sha256(
-    ikm: &[u8],      // Input keying material
-    salt: &[u8],     // Salt (can be empty)
-    info: &[u8],     // Context info
+    ikm: &[u8],  // Input keying material
+    salt: &[u8], // Salt (can be empty)
+    info: &[u8], // Context info
     output_len: usize,
 ) -> EnclaveResult<Vec<u8>> {
-    // Extract phase: PRK = HMAC-SHA256(salt, IKM)
-    let salt = if salt.is_empty() { &[0u8; 32] } else { salt };
+    const HASH_LEN: usize = 32;
+    if output_len > 255 * HASH_LEN {
+        return Err(EnclaveError::InvalidParameter);
+    }
 
-    // Simplified: just hash salt || ikm || info
-    // In production, would implement proper HKDF
-    let mut ctx = Sha256Context::new()?;
-    ctx.update(salt)?;
-    ctx.update(ikm)?;
-    ctx.update(info)?;
-    let prk = ctx.finalize()?;
+    // Extract: PRK = HMAC-SHA256(salt, IKM). An empty salt is treated as
+    // HashLen zero bytes (RFC 5869 section 2.2), not an empty HMAC key.
+    let zero_salt = [0u8; HASH_LEN];
+    let salt = if salt.is_empty() { &zero_salt[..] } else { salt };
+    let prk = hmac_sha256(salt, ikm)?;
 
-    // Expand phase (simplified)
+    // Expand: T(0) = "", T(i) = HMAC-SHA256(PRK, T(i-1) || info || i),
+    // concatenating T(1) || T(2) || ... until output_len bytes.
     let mut output = Vec::with_capacity(output_len);
+    let mut prev: Vec<u8> = Vec::new();
     let mut counter = 1u8;
-    let mut prev = Vec::new();
 
     while output.len() < output_len {
-        let mut ctx = Sha256Context::new()?;
-        ctx.update(&prev)?;
-        ctx.update(info)?;
-        ctx.update(&[counter])?;
-        let block = ctx.finalize()?;
+        let mut block_input = Vec::with_capacity(prev.len() + info.len() + 1);
+        block_input.extend_from_slice(&prev);
+        block_input.extend_from_slice(info);
+        block_input.push(counter);
+        let block = hmac_sha256(&prk, &block_input)?;
 
-        let needed = std::cmp::min(32, output_len - output.len());
+        let needed = std::cmp::min(HASH_LEN, output_len - output.len());
         output.extend_from_slice(&block[..needed]);
 
         prev = block.to_vec();
@@ -293,6 +1549,108 @@ pub fn hkdf_sha256(
     Ok(output)
 }
 
+/// Round constants for the Keccak-f[1600] permutation (FIPS 202, section
+/// 3.2.5).
+const KECCAK_RC: [u64; 24] = [
+    0x0000000000000001, 0x0000000000008082, 0x800000000000808a, 0x8000000080008000,
+    0x000000000000808b, 0x0000000080000001, 0x8000000080008081, 0x8000000000008009,
+    0x000000000000008a, 0x0000000000000088, 0x0000000080008009, 0x000000008000000a,
+    0x000000008000808b, 0x800000000000008b, 0x8000000000008089, 0x8000000000008003,
+    0x8000000000008002, 0x8000000000000080, 0x000000000000800a, 0x800000008000000a,
+    0x8000000080008081, 0x8000000000008080, 0x0000000080000001, 0x8000000080008008,
+];
+
+/// Rotation offsets for the rho step, indexed in the same traversal order as
+/// [`KECCAK_PI`].
+const KECCAK_RHO: [u32; 24] = [
+    1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44,
+];
+
+/// Lane permutation for the pi step.
+const KECCAK_PI: [usize; 24] = [
+    10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1,
+];
+
+/// The Keccak-f[1600] permutation over a 5x5 array of 64-bit lanes (theta,
+/// rho, pi, chi, iota), run for 24 rounds.
+fn keccak_f1600(a: &mut [u64; 25]) {
+    for round_rc in KECCAK_RC.iter() {
+        // Theta
+        let mut c = [0u64; 5];
+        for x in 0..5 {
+            for y in 0..5 {
+                c[x] ^= a[x + 5 * y];
+            }
+        }
+        for x in 0..5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                a[x + 5 * y] ^= d;
+            }
+        }
+
+        // Rho and pi: lane (x, y) moves to (y, 2x + 3y) rotated by a
+        // fixed offset; KECCAK_PI/KECCAK_RHO encode this as a single
+        // traversal starting from lane 1.
+        let mut last = a[1];
+        for i in 0..24 {
+            let dest = KECCAK_PI[i];
+            let tmp = a[dest];
+            a[dest] = last.rotate_left(KECCAK_RHO[i]);
+            last = tmp;
+        }
+
+        // Chi
+        for y in 0..5 {
+            let row: [u64; 5] = [
+                a[5 * y], a[5 * y + 1], a[5 * y + 2], a[5 * y + 3], a[5 * y + 4],
+            ];
+            for x in 0..5 {
+                a[x + 5 * y] = row[x] ^ ((!row[(x + 1) % 5]) & row[(x + 2) % 5]);
+            }
+        }
+
+        // Iota
+        a[0] ^= round_rc;
+    }
+}
+
+/// Compute the Keccak-256 hash used by Ethereum- and Neo-style chains.
+///
+/// This is the original Keccak sponge (rate 1088 bits / capacity 512 bits,
+/// `0x01` domain-separator padding) and not NIST SHA3-256, which finalized
+/// with a different padding byte (`0x06`) after Keccak was submitted to the
+/// SHA-3 competition; `sgx_tcrypto` exposes neither, so the sponge
+/// construction and permutation are implemented here directly per the
+/// Keccak reference specification.
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    const RATE_BYTES: usize = 136; // 1088 bits
+
+    let mut state = [0u64; 25];
+
+    let mut input = data.to_vec();
+    input.push(0x01);
+    while input.len() % RATE_BYTES != 0 {
+        input.push(0);
+    }
+    *input.last_mut().unwrap() ^= 0x80;
+
+    for block in input.chunks(RATE_BYTES) {
+        for (i, lane) in block.chunks(8).enumerate() {
+            let mut lane_bytes = [0u8; 8];
+            lane_bytes.copy_from_slice(lane);
+            state[i] ^= u64::from_le_bytes(lane_bytes);
+        }
+        keccak_f1600(&mut state);
+    }
+
+    let mut out = [0u8; 32];
+    for i in 0..4 {
+        out[i * 8..i * 8 + 8].copy_from_slice(&state[i].to_le_bytes());
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -313,6 +1671,173 @@ mod tests {
         assert!(keypair.verify(data, &signature).unwrap());
     }
 
+    #[test]
+    fn test_secp256k1_sign_recover() {
+        let keypair = Secp256k1KeyPair::generate().unwrap();
+        let message_hash = sha256(b"test message").unwrap();
+
+        let signature = keypair.sign_recoverable(&message_hash).unwrap();
+        let recovered = secp256k1_recover(&message_hash, &signature).unwrap();
+
+        assert_eq!(recovered.to_vec(), keypair.public_key_bytes().to_vec());
+    }
+
+    #[test]
+    fn test_secp256k1_sign_is_deterministic() {
+        let keypair = Secp256k1KeyPair::generate().unwrap();
+        let message_hash = sha256(b"test message").unwrap();
+
+        let sig1 = keypair.sign(&message_hash).unwrap();
+        let sig2 = keypair.sign(&message_hash).unwrap();
+        assert_eq!(sig1.to_vec(), sig2.to_vec());
+    }
+
+    #[test]
+    fn test_schnorr_sign_verify() {
+        let keypair = SchnorrKeyPair::generate().unwrap();
+        let message = sha256(b"taproot spend").unwrap();
+
+        let signature = keypair.sign(&message).unwrap();
+        assert!(schnorr_verify(&keypair.public_key_bytes(), &message, &signature).unwrap());
+    }
+
+    #[test]
+    fn test_ecdsa_der_sign_verify_roundtrip() {
+        let keypair = EcdsaKeyPair::generate().unwrap();
+        let data = b"der encoded message";
+
+        let der_sig = keypair.sign_der(data).unwrap();
+        assert!(keypair.verify_encoded(data, &der_sig).unwrap());
+
+        // The compact form must still verify through the same entry point.
+        let compact_sig = keypair.sign(data).unwrap();
+        assert!(keypair.verify_encoded(data, &compact_sig).unwrap());
+    }
+
+    #[test]
+    fn test_secp256k1_compressed_public_key_roundtrip() {
+        let keypair = Secp256k1KeyPair::generate().unwrap();
+        let compressed = keypair.public_key_bytes_encoded(PublicKeyEncoding::Compressed);
+        assert_eq!(compressed.len(), 33);
+
+        let point = secp256k1::decode_point(&compressed).unwrap();
+        let uncompressed = secp256k1::encode_uncompressed(&point);
+        assert_eq!(uncompressed.to_vec(), keypair.public_key_bytes().to_vec());
+    }
+
+    #[test]
+    fn test_secp256k1_ecdh_accepts_compressed_or_uncompressed_peer_key() {
+        let alice = Secp256k1KeyPair::generate().unwrap();
+        let bob = Secp256k1KeyPair::generate().unwrap();
+
+        let uncompressed_secret = alice.ecdh(&bob.public_key_bytes()).unwrap();
+        let compressed_secret = alice
+            .ecdh(&bob.public_key_bytes_encoded(PublicKeyEncoding::Compressed))
+            .unwrap();
+
+        assert_eq!(uncompressed_secret, compressed_secret);
+    }
+
+    #[test]
+    fn test_secp256k1_ecdh_agreement() {
+        let alice = Secp256k1KeyPair::generate().unwrap();
+        let bob = Secp256k1KeyPair::generate().unwrap();
+
+        let alice_secret = alice.ecdh(&bob.public_key_bytes()).unwrap();
+        let bob_secret = bob.ecdh(&alice.public_key_bytes()).unwrap();
+
+        assert_eq!(alice_secret, bob_secret);
+    }
+
+    #[test]
+    fn test_secp256k1_ecies_roundtrip() {
+        let recipient = Secp256k1KeyPair::generate().unwrap();
+        let plaintext = b"script input for the enclave only";
+
+        let ciphertext =
+            secp256k1_ecies_encrypt(&recipient.public_key_bytes(), plaintext).unwrap();
+        let decrypted =
+            secp256k1_ecies_decrypt(&recipient.private_key_bytes(), &ciphertext).unwrap();
+
+        assert_eq!(plaintext.as_slice(), decrypted.as_slice());
+    }
+
+    #[test]
+    fn test_secp256k1_ecies_rejects_tampered_ciphertext() {
+        let recipient = Secp256k1KeyPair::generate().unwrap();
+        let mut ciphertext =
+            secp256k1_ecies_encrypt(&recipient.public_key_bytes(), b"script input").unwrap();
+        *ciphertext.last_mut().unwrap() ^= 0xff;
+
+        assert!(secp256k1_ecies_decrypt(&recipient.private_key_bytes(), &ciphertext).is_err());
+    }
+
+    #[test]
+    fn test_p256_ecies_roundtrip() {
+        let recipient = EcdsaKeyPair::generate().unwrap();
+        let plaintext = b"script result for the caller only";
+
+        let ciphertext = p256_ecies_encrypt(&recipient.public_key_bytes(), plaintext).unwrap();
+        let decrypted = p256_ecies_decrypt(&recipient.private_key, &ciphertext).unwrap();
+
+        assert_eq!(plaintext.as_slice(), decrypted.as_slice());
+    }
+
+    #[test]
+    fn test_sha512_known_vector() {
+        // NIST test vector: SHA-512("abc")
+        let hash = sha512(b"abc");
+        let expected = "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39\
+                         a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f";
+        assert_eq!(hex_encode(&hash), expected);
+    }
+
+    #[test]
+    fn test_hmac_sha512_rfc4231_case1() {
+        // RFC 4231 test case 1.
+        let key = [0x0bu8; 20];
+        let mac = hmac_sha512(&key, b"Hi There");
+        let expected = "87aa7cdea5ef619d4ff0b4241a1d6cb02379f4e2ce4ec2787ad0b30545e17cdedaa833b\
+                         7d6b8a702038b274eaea3f4e4be9d914eeb61f1702e696c203a126854";
+        assert_eq!(hex_encode(&mac), expected);
+    }
+
+    #[test]
+    fn test_hmac_sha256_rfc4231_case1() {
+        // RFC 4231 test case 1.
+        let key = [0x0bu8; 20];
+        let mac = hmac_sha256(&key, b"Hi There").unwrap();
+        let expected = "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7";
+        assert_eq!(hex_encode(&mac), expected);
+    }
+
+    #[test]
+    fn test_hkdf_sha256_rfc5869_case1() {
+        // RFC 5869 Appendix A.1, Test Case 1.
+        let ikm = [0x0bu8; 22];
+        let salt: [u8; 13] = [
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c,
+        ];
+        let info: [u8; 10] = [0xf0, 0xf1, 0xf2, 0xf3, 0xf4, 0xf5, 0xf6, 0xf7, 0xf8, 0xf9];
+
+        let okm = hkdf_sha256(&ikm, &salt, &info, 42).unwrap();
+        let expected = "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865";
+        assert_eq!(hex_encode(&okm), expected);
+    }
+
+    #[test]
+    fn test_hkdf_sha256_rejects_oversized_output() {
+        assert!(hkdf_sha256(b"ikm", b"salt", b"info", 255 * 32 + 1).is_err());
+    }
+
+    fn hex_encode(bytes: &[u8]) -> String {
+        let mut out = String::with_capacity(bytes.len() * 2);
+        for b in bytes {
+            out.push_str(&format!("{:02x}", b));
+        }
+        out
+    }
+
     #[test]
     fn test_aes_gcm_roundtrip() {
         let key = generate_key().unwrap();
@@ -325,4 +1850,188 @@ mod tests {
 
         assert_eq!(plaintext.as_slice(), decrypted.as_slice());
     }
+
+    #[test]
+    fn test_aes_gcm_rejects_tampered_tag() {
+        let key = generate_key().unwrap();
+        let iv = [0u8; 12];
+        let (ciphertext, mut tag) = AesGcm::encrypt(&key, &iv, b"secret data", b"aad").unwrap();
+        tag[0] ^= 0xff;
+
+        assert!(AesGcm::decrypt(&key, &iv, &ciphertext, b"aad", &tag).is_err());
+    }
+
+    #[test]
+    fn test_aes256_block_cipher_fips197_vector() {
+        // FIPS-197 Appendix C.3 (AES-256).
+        let key: [u8; 32] = [
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d,
+            0x0e, 0x0f, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b,
+            0x1c, 0x1d, 0x1e, 0x1f,
+        ];
+        let plaintext: [u8; 16] = [
+            0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd,
+            0xee, 0xff,
+        ];
+        let expected_ciphertext: [u8; 16] = [
+            0x8e, 0xa2, 0xb7, 0xca, 0x51, 0x67, 0x45, 0xbf, 0xea, 0xfc, 0x49, 0x90, 0x4b, 0x49,
+            0x60, 0x89,
+        ];
+
+        let round_keys = aes256_key_expansion(&key);
+        let ciphertext = aes256_encrypt_block(&round_keys, &plaintext);
+        assert_eq!(ciphertext, expected_ciphertext);
+
+        let roundtrip = aes256_decrypt_block(&round_keys, &ciphertext);
+        assert_eq!(roundtrip, plaintext);
+    }
+
+    #[test]
+    fn test_aes128_block_cipher_fips197_vector() {
+        // FIPS-197 Appendix C.1 (AES-128).
+        let key: [u8; 16] = [
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d,
+            0x0e, 0x0f,
+        ];
+        let plaintext: [u8; 16] = [
+            0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd,
+            0xee, 0xff,
+        ];
+        let expected_ciphertext: [u8; 16] = [
+            0x69, 0xc4, 0xe0, 0xd8, 0x6a, 0x7b, 0x04, 0x30, 0xd8, 0xcd, 0xb7, 0x80, 0x70, 0xb4,
+            0xc5, 0x5a,
+        ];
+
+        let round_keys = aes128_key_expansion(&key);
+        let ciphertext = aes128_encrypt_block(&round_keys, &plaintext);
+        assert_eq!(ciphertext, expected_ciphertext);
+    }
+
+    #[test]
+    fn test_aes128_ctr_nist_sp800_38a_vector() {
+        // NIST SP 800-38A F.5.1, AES-128-CTR, first block.
+        let key: [u8; 16] = [
+            0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf,
+            0x4f, 0x3c,
+        ];
+        let icb: [u8; 16] = [
+            0xf0, 0xf1, 0xf2, 0xf3, 0xf4, 0xf5, 0xf6, 0xf7, 0xf8, 0xf9, 0xfa, 0xfb, 0xfc, 0xfd,
+            0xfe, 0xff,
+        ];
+        let plaintext: [u8; 16] = [
+            0x6b, 0xc1, 0xbe, 0xe2, 0x2e, 0x40, 0x9f, 0x96, 0xe9, 0x3d, 0x7e, 0x11, 0x73, 0x93,
+            0x17, 0x2a,
+        ];
+        let expected_ciphertext: [u8; 16] = [
+            0x87, 0x4d, 0x61, 0x91, 0xb6, 0x20, 0xe3, 0x26, 0x1b, 0xef, 0x68, 0x64, 0x99, 0x0d,
+            0xb6, 0xce,
+        ];
+
+        let ciphertext = aes128_ctr_xor(&key, &icb, &plaintext);
+        assert_eq!(ciphertext, expected_ciphertext);
+
+        let decrypted = aes128_ctr_xor(&key, &icb, &ciphertext);
+        assert_eq!(decrypted, plaintext);
+    }
+
+    #[test]
+    fn test_aes_ctr_nist_sp800_38a_vector() {
+        // NIST SP 800-38A F.5.5, AES-256-CTR, first block.
+        let key: [u8; 32] = [
+            0x60, 0x3d, 0xeb, 0x10, 0x15, 0xca, 0x71, 0xbe, 0x2b, 0x73, 0xae, 0xf0, 0x85, 0x7d,
+            0x77, 0x81, 0x1f, 0x35, 0x2c, 0x07, 0x3b, 0x61, 0x08, 0xd7, 0x2d, 0x98, 0x10, 0xa3,
+            0x09, 0x14, 0xdf, 0xf4,
+        ];
+        let icb: [u8; 16] = [
+            0xf0, 0xf1, 0xf2, 0xf3, 0xf4, 0xf5, 0xf6, 0xf7, 0xf8, 0xf9, 0xfa, 0xfb, 0xfc, 0xfd,
+            0xfe, 0xff,
+        ];
+        let plaintext: [u8; 16] = [
+            0x6b, 0xc1, 0xbe, 0xe2, 0x2e, 0x40, 0x9f, 0x96, 0xe9, 0x3d, 0x7e, 0x11, 0x73, 0x93,
+            0x17, 0x2a,
+        ];
+        let expected_ciphertext: [u8; 16] = [
+            0x60, 0x1e, 0xc3, 0x13, 0x77, 0x57, 0x89, 0xa5, 0xb7, 0xa7, 0xf5, 0x04, 0xbb, 0xf3,
+            0xd2, 0x28,
+        ];
+
+        let ciphertext = AesCtr::encrypt(&key, &icb, &plaintext).unwrap();
+        assert_eq!(ciphertext, expected_ciphertext);
+
+        let decrypted = AesCtr::decrypt(&key, &icb, &ciphertext).unwrap();
+        assert_eq!(decrypted, plaintext);
+    }
+
+    #[test]
+    fn test_aes_cbc_roundtrip_with_padding() {
+        let key = generate_key().unwrap();
+        let iv = [0u8; 16];
+        let plaintext = b"a message that does not land on a block boundary";
+
+        let ciphertext = AesCbc::encrypt(&key, &iv, plaintext).unwrap();
+        assert_eq!(ciphertext.len() % 16, 0);
+        let decrypted = AesCbc::decrypt(&key, &iv, &ciphertext).unwrap();
+
+        assert_eq!(plaintext.as_slice(), decrypted.as_slice());
+    }
+
+    #[test]
+    fn test_aes_cbc_rejects_corrupted_padding() {
+        let key = generate_key().unwrap();
+        let iv = [0u8; 16];
+        let mut ciphertext = AesCbc::encrypt(&key, &iv, b"exactly one block").unwrap();
+        *ciphertext.last_mut().unwrap() ^= 0xff;
+
+        assert!(AesCbc::decrypt(&key, &iv, &ciphertext).is_err());
+    }
+
+    #[test]
+    fn test_aes_encrypt_dispatches_by_mode() {
+        let key = generate_key().unwrap();
+        let plaintext = b"dispatch me";
+
+        let gcm = aes_encrypt(AesMode::Gcm, &key, &[0u8; 12], plaintext, b"aad").unwrap();
+        assert_eq!(aes_decrypt(AesMode::Gcm, &key, &[0u8; 12], &gcm, b"aad").unwrap(), plaintext);
+
+        let ctr = aes_encrypt(AesMode::Ctr, &key, &[0u8; 16], plaintext, &[]).unwrap();
+        assert_eq!(aes_decrypt(AesMode::Ctr, &key, &[0u8; 16], &ctr, &[]).unwrap(), plaintext);
+
+        let cbc = aes_encrypt(AesMode::Cbc, &key, &[0u8; 16], plaintext, &[]).unwrap();
+        assert_eq!(aes_decrypt(AesMode::Cbc, &key, &[0u8; 16], &cbc, &[]).unwrap(), plaintext);
+    }
+
+    #[test]
+    fn test_keccak256_empty_input() {
+        // Well-known empty-input Keccak-256 digest (e.g. Ethereum's
+        // EmptyCodeHash / EmptyRootHash constants).
+        let digest = keccak256(b"");
+        assert_eq!(
+            hex_encode(&digest),
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+    }
+
+    #[test]
+    fn test_keccak256_abc() {
+        let digest = keccak256(b"abc");
+        assert_eq!(
+            hex_encode(&digest),
+            "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45"
+        );
+    }
+
+    #[test]
+    fn test_ct_eq() {
+        assert!(ct_eq(b"matching tag", b"matching tag"));
+        assert!(!ct_eq(b"matching tag", b"MATCHING TAG"));
+        assert!(!ct_eq(b"short", b"a longer slice"));
+    }
+
+    #[test]
+    fn test_zeroizing_derefs_to_inner_value() {
+        let mut secret = Zeroizing::new([0x11u8; 16]);
+        assert_eq!(*secret, [0x11u8; 16]);
+        secret[0] = 0x22;
+        assert_eq!(secret[0], 0x22);
+    }
 }
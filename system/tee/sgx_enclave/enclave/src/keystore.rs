@@ -0,0 +1,152 @@
+//! Serialization for sealing/unsealing the enclave's in-memory key vault.
+//!
+//! `EnclaveState.keys` only lives for the lifetime of the enclave process -
+//! destroying and reloading the enclave (a host restart, an upgrade) throws
+//! away every generated key. This module turns that map into a flat byte
+//! buffer suitable for [`crate::sealing::seal_data`], and back, so
+//! `ecall_seal_keystore`/`ecall_unseal_keystore` can persist it as one
+//! sealed blob on the host's disk and restore it on the next
+//! `ecall_initialize`.
+//!
+//! Mirroring `SgxInternalUnsealedData`'s payload/additional-data split, the
+//! serialized keys are the sealed *payload* while a small version/enclave-id
+//! tag goes in the *additional authenticated data* (see [`build_aad`]): it
+//! isn't secret, but it is integrity-protected, so a restored keystore can
+//! be tied back to the enclave identity that sealed it and rejected if the
+//! blob was swapped out from under that identity.
+
+use std::prelude::v1::*;
+use std::collections::HashMap;
+use std::string::String;
+use std::vec::Vec;
+
+use crate::crypto::Zeroizing;
+use crate::types::{EnclaveError, EnclaveResult};
+use crate::{KeyEntry, KeyType};
+
+/// Version tag written into the keystore AAD. Bumped if the serialized
+/// layout ever changes incompatibly.
+pub const KEYSTORE_VERSION: u32 = 1;
+
+/// Size of the AAD produced by [`build_aad`]: a 4-byte version plus the
+/// 32-byte enclave id.
+pub const AAD_LEN: usize = 4 + 32;
+
+fn key_type_tag(key_type: KeyType) -> u8 {
+    match key_type {
+        KeyType::EcdsaP256 => 0,
+        KeyType::EcdsaSecp256k1 => 1,
+        KeyType::Aes256 => 2,
+    }
+}
+
+fn key_type_from_tag(tag: u8) -> EnclaveResult<KeyType> {
+    match tag {
+        0 => Ok(KeyType::EcdsaP256),
+        1 => Ok(KeyType::EcdsaSecp256k1),
+        2 => Ok(KeyType::Aes256),
+        _ => Err(EnclaveError::UnsealError(format!("unknown key type tag: {}", tag))),
+    }
+}
+
+/// Builds the additional authenticated data binding a sealed keystore to
+/// the enclave identity that sealed it: `version (u32 LE) ‖ enclave_id (32)`.
+pub fn build_aad(enclave_id: &[u8; 32]) -> [u8; AAD_LEN] {
+    let mut aad = [0u8; AAD_LEN];
+    aad[..4].copy_from_slice(&KEYSTORE_VERSION.to_le_bytes());
+    aad[4..].copy_from_slice(enclave_id);
+    aad
+}
+
+/// Validates a keystore AAD against the current enclave identity, rejecting
+/// a mismatched or tampered tag (an unseal against the wrong enclave, or a
+/// version this build doesn't understand).
+pub fn verify_aad(aad: &[u8], enclave_id: &[u8; 32]) -> EnclaveResult<()> {
+    if aad.len() != AAD_LEN {
+        return Err(EnclaveError::UnsealError("keystore AAD has the wrong length".to_string()));
+    }
+    let mut version_bytes = [0u8; 4];
+    version_bytes.copy_from_slice(&aad[..4]);
+    let version = u32::from_le_bytes(version_bytes);
+    if version > KEYSTORE_VERSION {
+        return Err(EnclaveError::UnsealError(format!("unsupported keystore version: {}", version)));
+    }
+    if &aad[4..] != enclave_id {
+        return Err(EnclaveError::UnsealError("keystore is bound to a different enclave".to_string()));
+    }
+    Ok(())
+}
+
+/// Flattens the key vault into `count (u32) ‖ entry*`, where each entry is
+/// `key_id_len (u32) ‖ key_id ‖ key_type (u8) ‖ priv_len (u32) ‖ priv ‖
+/// pub_len (u32) ‖ pub`.
+pub fn serialize(keys: &HashMap<String, KeyEntry>) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(&(keys.len() as u32).to_le_bytes());
+
+    for (key_id, entry) in keys.iter() {
+        let id_bytes = key_id.as_bytes();
+        out.extend_from_slice(&(id_bytes.len() as u32).to_le_bytes());
+        out.extend_from_slice(id_bytes);
+        out.push(key_type_tag(entry.key_type));
+        out.extend_from_slice(&(entry.private_key.len() as u32).to_le_bytes());
+        out.extend_from_slice(&entry.private_key);
+        out.extend_from_slice(&(entry.public_key.len() as u32).to_le_bytes());
+        out.extend_from_slice(&entry.public_key);
+    }
+
+    out
+}
+
+/// Inverse of [`serialize`]. Rejects a truncated or malformed buffer rather
+/// than panicking, since it is driven by unsealed (but not otherwise
+/// validated) bytes.
+pub fn deserialize(bytes: &[u8]) -> EnclaveResult<HashMap<String, KeyEntry>> {
+    let mut keys = HashMap::new();
+    let mut offset = 0usize;
+
+    let count = read_u32(bytes, &mut offset)?;
+    for _ in 0..count {
+        let id_len = read_u32(bytes, &mut offset)? as usize;
+        let id_bytes = read_bytes(bytes, &mut offset, id_len)?;
+        let key_id = String::from_utf8(id_bytes)
+            .map_err(|_| EnclaveError::UnsealError("key id is not valid UTF-8".to_string()))?;
+
+        let tag = read_u8(bytes, &mut offset)?;
+        let key_type = key_type_from_tag(tag)?;
+
+        let priv_len = read_u32(bytes, &mut offset)? as usize;
+        let private_key = read_bytes(bytes, &mut offset, priv_len)?;
+
+        let pub_len = read_u32(bytes, &mut offset)? as usize;
+        let public_key = read_bytes(bytes, &mut offset, pub_len)?;
+
+        keys.insert(key_id, KeyEntry { key_type, private_key: Zeroizing::new(private_key), public_key });
+    }
+
+    Ok(keys)
+}
+
+fn read_u8(bytes: &[u8], offset: &mut usize) -> EnclaveResult<u8> {
+    let b = *bytes.get(*offset).ok_or_else(truncated)?;
+    *offset += 1;
+    Ok(b)
+}
+
+fn read_u32(bytes: &[u8], offset: &mut usize) -> EnclaveResult<u32> {
+    let slice = bytes.get(*offset..*offset + 4).ok_or_else(truncated)?;
+    let mut b = [0u8; 4];
+    b.copy_from_slice(slice);
+    *offset += 4;
+    Ok(u32::from_le_bytes(b))
+}
+
+fn read_bytes(bytes: &[u8], offset: &mut usize, len: usize) -> EnclaveResult<Vec<u8>> {
+    let slice = bytes.get(*offset..*offset + len).ok_or_else(truncated)?;
+    *offset += len;
+    Ok(slice.to_vec())
+}
+
+fn truncated() -> EnclaveError {
+    EnclaveError::UnsealError("keystore buffer is truncated".to_string())
+}
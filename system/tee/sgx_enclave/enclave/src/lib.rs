@@ -22,7 +22,8 @@
 //! │  ┌─────────────────────────────────────────────────────────┐ │
 //! │  │  Core Modules                                            │ │
 //! │  │  - crypto: AES-GCM, ECDSA, SHA256, RIPEMD160            │ │
-//! │  │  - sealing: Data sealing with MRSIGNER policy           │ │
+//! │  │  - sealing: Data sealing with MRENCLAVE/MRSIGNER policy │ │
+//! │  │  - rollback: Anti-rollback monotonic counters            │ │
 //! │  │  - attestation: Quote generation                         │ │
 //! │  │  - script: QuickJS JavaScript engine                     │ │
 //! │  └─────────────────────────────────────────────────────────┘ │
@@ -37,6 +38,8 @@ extern crate sgx_types;
 extern crate sgx_tcrypto;
 extern crate sgx_tseal;
 extern crate sgx_tse;
+extern crate sgx_tservice;
+extern crate sgx_trts;
 extern crate sgx_rand;
 
 use std::prelude::v1::*;
@@ -47,12 +50,21 @@ use std::collections::HashMap;
 
 use sgx_types::*;
 use sgx_tcrypto::*;
-use sgx_tseal::SgxSealedData;
 use sgx_tse::*;
+use sgx_trts::trts::rsgx_read_rand;
 
 mod crypto;
 mod sealing;
 mod attestation;
+mod dcap;
+mod policy;
+mod ratls;
+mod secp256k1;
+mod bip32;
+mod marshal;
+mod rollback;
+mod keystore;
+mod web3_keystore;
 mod types;
 
 use types::*;
@@ -70,17 +82,49 @@ struct EnclaveState {
     enclave_id: [u8; 32],
     keys: HashMap<String, KeyEntry>,
     sealed_data: HashMap<String, Vec<u8>>,
+    /// Ephemeral key-exchange private keys, keyed by session id, awaiting
+    /// [`ecall_session_complete`]. Removed as soon as a session is completed
+    /// (or never persisted if the handshake is abandoned). Zeroized on
+    /// removal/replacement since this is secret key material, same as
+    /// `keys`.
+    sessions: HashMap<String, crypto::Zeroizing<[u8; 32]>>,
+    /// Ephemeral key-exchange key pairs for an in-flight
+    /// [`ecall_secure_handshake_init`]/[`ecall_secure_handshake_finish`]
+    /// pair, keyed by session id. Kept separate from `sessions` since this
+    /// handshake is attestation-bound to a DCAP quote rather than a local
+    /// report, and establishes directional keys instead of a single shared
+    /// one.
+    handshake_sessions: HashMap<String, HandshakeState>,
+    /// Established UKEY2-style secure channels, keyed by session id.
+    secure_sessions: HashMap<String, SessionKeys>,
 }
 
-struct KeyEntry {
-    key_type: KeyType,
-    private_key: Vec<u8>,
-    public_key: Vec<u8>,
+/// Directional AES-256-GCM keys for an [`ecall_secure_handshake_finish`]-
+/// established channel, plus the per-direction message counters that seed
+/// [`ecall_secure_session_encrypt`]/[`ecall_secure_session_decrypt`]'s GCM
+/// IV. Counters only ever increment, so messages must be delivered in
+/// order - a dropped or reordered message desynchronizes the counter and
+/// the next decrypt fails rather than silently reusing a nonce.
+pub(crate) struct SessionKeys {
+    pub(crate) send_key: [u8; 32],
+    pub(crate) recv_key: [u8; 32],
+    pub(crate) send_counter: u64,
+    pub(crate) recv_counter: u64,
+}
+
+pub(crate) struct KeyEntry {
+    pub(crate) key_type: KeyType,
+    /// Zeroized on drop - including when an entry is replaced or removed
+    /// from `EnclaveState.keys` - since this is the enclave's long-lived
+    /// plaintext store of every generated/imported private key.
+    pub(crate) private_key: crypto::Zeroizing<Vec<u8>>,
+    pub(crate) public_key: Vec<u8>,
 }
 
 #[derive(Clone, Copy)]
-enum KeyType {
+pub(crate) enum KeyType {
     EcdsaP256,
+    EcdsaSecp256k1,
     Aes256,
 }
 
@@ -91,6 +135,9 @@ impl EnclaveState {
             enclave_id: [0u8; 32],
             keys: HashMap::new(),
             sealed_data: HashMap::new(),
+            sessions: HashMap::new(),
+            handshake_sessions: HashMap::new(),
+            secure_sessions: HashMap::new(),
         }
     }
 }
@@ -101,13 +148,26 @@ impl EnclaveState {
 
 /// Initialize the enclave and generate enclave ID.
 /// Must be called before any other ECALL.
+///
+/// To restore a durable identity across a destroy/re-create cycle, the host
+/// may pass back a previously-returned `enclave_id_out` value as
+/// `enclave_id_in` (32 bytes; null/zero-length to generate a fresh random
+/// id instead). If `sealed_keystore` is also non-null, it is unsealed (see
+/// [`ecall_seal_keystore`]) and loaded into the key vault, but only after
+/// its AAD's enclave-id tag is checked against the (restored or freshly
+/// generated) `state.enclave_id` - a keystore sealed under a different
+/// identity is rejected rather than silently ignored.
 #[no_mangle]
 pub extern "C" fn ecall_initialize(
     enclave_id_out: *mut u8,
     enclave_id_len: usize,
+    enclave_id_in: *const u8,
+    enclave_id_in_len: usize,
+    sealed_keystore: *const u8,
+    sealed_keystore_len: usize,
 ) -> sgx_status_t {
-    if enclave_id_out.is_null() || enclave_id_len < 32 {
-        return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+    if let Err(e) = marshal::check_out_buf(enclave_id_out, enclave_id_len, 32) {
+        return e;
     }
 
     let mut state = match ENCLAVE_STATE.lock() {
@@ -127,19 +187,144 @@ pub extern "C" fn ecall_initialize(
         return sgx_status_t::SGX_SUCCESS;
     }
 
-    // Generate random enclave ID
-    let mut rand_id = [0u8; 32];
-    match sgx_rand::rand::Rng::fill_bytes(&mut sgx_rand::rand::thread_rng(), &mut rand_id) {
-        Ok(_) => {},
+    let restored_id = match marshal::copy_in(enclave_id_in, enclave_id_in_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    let enclave_id = if restored_id.len() == 32 {
+        let mut id = [0u8; 32];
+        id.copy_from_slice(&restored_id);
+        id
+    } else {
+        let mut rand_id = [0u8; 32];
+        match sgx_rand::rand::Rng::fill_bytes(&mut sgx_rand::rand::thread_rng(), &mut rand_id) {
+            Ok(_) => {},
+            Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
+        }
+        rand_id
+    };
+
+    state.enclave_id = enclave_id;
+    state.initialized = true;
+
+    let keystore_slice = match marshal::copy_in(sealed_keystore, sealed_keystore_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    if !keystore_slice.is_empty() {
+        let (payload, aad) = match sealing::unseal_data(&keystore_slice) {
+            Ok(v) => v,
+            Err(_) => return sgx_status_t::SGX_ERROR_MAC_MISMATCH,
+        };
+        if keystore::verify_aad(&aad, &state.enclave_id).is_err() {
+            return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+        }
+        let keys = match keystore::deserialize(&payload) {
+            Ok(k) => k,
+            Err(_) => return sgx_status_t::SGX_ERROR_INVALID_PARAMETER,
+        };
+        state.keys = keys;
+    }
+
+    unsafe {
+        std::ptr::copy_nonoverlapping(enclave_id.as_ptr(), enclave_id_out, 32);
+    }
+
+    sgx_status_t::SGX_SUCCESS
+}
+
+// =============================================================================
+// ECALL: Sealed Key Vault Persistence
+// =============================================================================
+
+/// Seals the entire in-memory key vault (`EnclaveState.keys`) into one
+/// `SgxSealedData` blob, so the host can persist it to disk and feed it
+/// back to a future [`ecall_initialize`] call to restore every generated
+/// key. The blob's AAD binds it to this enclave's identity (see
+/// [`keystore::build_aad`]) rather than just to MRENCLAVE/MRSIGNER, so a
+/// keystore swapped in under the wrong enclave instance is rejected on
+/// unseal even if it came from an enclave signed by the same key.
+#[no_mangle]
+pub extern "C" fn ecall_seal_keystore(
+    policy: u8,
+    sealed_out: *mut u8,
+    sealed_buf_len: usize,
+    sealed_len_out: *mut usize,
+) -> sgx_status_t {
+    if let Err(e) = marshal::check_out_ptr(sealed_len_out) {
+        return e;
+    }
+    let policy = match sealing_policy_from_wire(policy) {
+        Ok(p) => p,
+        Err(e) => return e,
+    };
+
+    let state = match ENCLAVE_STATE.lock() {
+        Ok(s) => s,
         Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
+    };
+    if !state.initialized {
+        return sgx_status_t::SGX_ERROR_INVALID_STATE;
     }
 
-    state.enclave_id = rand_id;
-    state.initialized = true;
+    let payload = keystore::serialize(&state.keys);
+    let aad = keystore::build_aad(&state.enclave_id);
+
+    let sealed_size = sealing::calc_sealed_size(payload.len(), aad.len());
+    if let Err(e) = marshal::check_out_buf(sealed_out, sealed_buf_len, sealed_size) {
+        unsafe { *sealed_len_out = sealed_size; }
+        return e;
+    }
+
+    let sealed_bytes = match sealing::seal_data(&payload, &aad, policy) {
+        Ok(b) => b,
+        Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
+    };
 
     unsafe {
-        std::ptr::copy_nonoverlapping(rand_id.as_ptr(), enclave_id_out, 32);
+        std::ptr::copy_nonoverlapping(sealed_bytes.as_ptr(), sealed_out, sealed_bytes.len());
+        *sealed_len_out = sealed_bytes.len();
+    }
+
+    sgx_status_t::SGX_SUCCESS
+}
+
+/// Unseals a blob produced by [`ecall_seal_keystore`] and replaces the
+/// in-memory key vault with its contents, after checking the blob's AAD is
+/// bound to this enclave's identity. Unlike [`ecall_initialize`]'s restore
+/// path, this can be called at any time to reload a keystore into an
+/// already-running enclave.
+#[no_mangle]
+pub extern "C" fn ecall_unseal_keystore(
+    sealed: *const u8,
+    sealed_len: usize,
+) -> sgx_status_t {
+    let sealed_slice = match marshal::copy_in_required(sealed, sealed_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    let mut state = match ENCLAVE_STATE.lock() {
+        Ok(s) => s,
+        Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
+    };
+    if !state.initialized {
+        return sgx_status_t::SGX_ERROR_INVALID_STATE;
+    }
+
+    let (payload, aad) = match sealing::unseal_data(&sealed_slice) {
+        Ok(v) => v,
+        Err(_) => return sgx_status_t::SGX_ERROR_MAC_MISMATCH,
+    };
+    if keystore::verify_aad(&aad, &state.enclave_id).is_err() {
+        return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
     }
+    let keys = match keystore::deserialize(&payload) {
+        Ok(k) => k,
+        Err(_) => return sgx_status_t::SGX_ERROR_INVALID_PARAMETER,
+    };
+    state.keys = keys;
 
     sgx_status_t::SGX_SUCCESS
 }
@@ -148,99 +333,177 @@ pub extern "C" fn ecall_initialize(
 // ECALL: Seal Data (using SGX sealing key from EGETKEY)
 // =============================================================================
 
+/// Sealing-key policy selector for the ECALL wire format: `0` binds the
+/// blob to this enclave's signer (MRSIGNER, survives enclave upgrades),
+/// `1` binds it to this exact enclave build (MRENCLAVE).
+fn sealing_policy_from_wire(policy: u8) -> Result<sealing::SealingPolicy, sgx_status_t> {
+    match policy {
+        0 => Ok(sealing::SealingPolicy::MrSigner),
+        1 => Ok(sealing::SealingPolicy::MrEnclave),
+        _ => Err(sgx_status_t::SGX_ERROR_INVALID_PARAMETER),
+    }
+}
+
+/// Wire values for the public-key export format accepted by
+/// [`ecall_generate_ecdsa_keypair`] and [`ecall_export_public_key`]: `0` for
+/// the 65-byte uncompressed SEC1 point, `1` for the 33-byte compressed form.
+fn public_key_encoding_from_wire(format: u8) -> Result<PublicKeyEncoding, sgx_status_t> {
+    match format {
+        0 => Ok(PublicKeyEncoding::Uncompressed),
+        1 => Ok(PublicKeyEncoding::Compressed),
+        _ => Err(sgx_status_t::SGX_ERROR_INVALID_PARAMETER),
+    }
+}
+
 /// Seal data using the enclave's sealing key.
-/// Uses MRSIGNER policy so data can be unsealed by any enclave signed by the same key.
+///
+/// `key_policy` is a raw SGX key-request policy bitmask: any combination of
+/// `sealing::SGX_KEYPOLICY_*` (MRENCLAVE, MRSIGNER, NOISVPRODID, and the
+/// KSS bits CONFIGID/ISVFAMILYID/ISVEXTPRODID); `0` defaults to plain
+/// MRSIGNER, matching [`sealing::SealingPolicy::MrSigner`]. `attribute_mask_flags`
+/// / `attribute_mask_xfrm` and `misc_mask` select which attribute/misc-select
+/// bits must match between sealing and unsealing; pass all-zero for each to
+/// fall back to the SDK's own defaults ([`sealing::DEFAULT_ATTRIBUTE_MASK`] /
+/// [`sealing::DEFAULT_MISC_MASK`]).
+///
+/// When `counter_uuid` is non-null (a 16-byte monotonic counter UUID, as
+/// returned by [`ecall_create_monotonic_counter`]), the sealed blob is bound
+/// to `counter_value` so that [`ecall_unseal_data`] can reject a stale copy
+/// whose counter has since been incremented (anti-rollback protection).
+/// Pass a null `counter_uuid` to seal without rollback protection.
 #[no_mangle]
 pub extern "C" fn ecall_seal_data(
     plaintext: *const u8,
     plaintext_len: usize,
     additional_data: *const u8,
     additional_len: usize,
+    key_policy: u16,
+    attribute_mask_flags: u64,
+    attribute_mask_xfrm: u64,
+    misc_mask: u32,
+    counter_uuid: *const u8,
+    counter_value: u32,
     sealed_out: *mut u8,
     sealed_buf_len: usize,
     sealed_len_out: *mut usize,
 ) -> sgx_status_t {
-    if plaintext.is_null() || sealed_out.is_null() || sealed_len_out.is_null() {
-        return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+    if let Err(e) = marshal::check_out_ptr(sealed_len_out) {
+        return e;
     }
 
-    let plaintext_slice = unsafe { std::slice::from_raw_parts(plaintext, plaintext_len) };
-
-    let additional_slice = if additional_data.is_null() || additional_len == 0 {
-        &[]
+    let key_policy = if key_policy == 0 { sealing::SGX_KEYPOLICY_MRSIGNER } else { key_policy };
+    let attribute_mask = if attribute_mask_flags == 0 && attribute_mask_xfrm == 0 {
+        sealing::DEFAULT_ATTRIBUTE_MASK
     } else {
-        unsafe { std::slice::from_raw_parts(additional_data, additional_len) }
+        sgx_attributes_t { flags: attribute_mask_flags, xfrm: attribute_mask_xfrm }
+    };
+    let misc_mask = if misc_mask == 0 { sealing::DEFAULT_MISC_MASK } else { misc_mask };
+
+    let plaintext_slice = match marshal::copy_in_required(plaintext, plaintext_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let additional_slice = match marshal::copy_in(additional_data, additional_len) {
+        Ok(v) => v,
+        Err(e) => return e,
     };
 
-    // Calculate required sealed data size
-    let sealed_size = SgxSealedData::<[u8]>::calc_raw_sealed_data_size(
-        additional_slice.len() as u32,
-        plaintext_len as u32,
-    ) as usize;
+    let aad = if counter_uuid.is_null() {
+        additional_slice
+    } else {
+        let uuid_slice = match marshal::copy_in_required(counter_uuid, 16) {
+            Ok(v) => v,
+            Err(e) => return e,
+        };
+        let mut uuid = [0u8; 16];
+        uuid.copy_from_slice(&uuid_slice);
+        rollback::bind_aad(&additional_slice, rollback::CounterBinding { uuid, value: counter_value })
+    };
 
-    if sealed_buf_len < sealed_size {
+    let sealed_size = sealing::calc_sealed_size(plaintext_slice.len(), aad.len());
+    if let Err(e) = marshal::check_out_buf(sealed_out, sealed_buf_len, sealed_size) {
         unsafe { *sealed_len_out = sealed_size; }
-        return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+        return e;
     }
 
-    // Seal the data using MRSIGNER policy
-    let sealed_data = match SgxSealedData::<[u8]>::seal_data(
-        additional_slice,
-        plaintext_slice,
+    let sealed_bytes = match sealing::seal_data_with_policy(
+        &plaintext_slice,
+        &aad,
+        key_policy,
+        attribute_mask,
+        misc_mask,
     ) {
-        Ok(sd) => sd,
-        Err(e) => return e,
+        Ok(b) => b,
+        Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
     };
 
-    // Copy sealed data to output buffer
-    let sealed_bytes = sealed_data.into_raw_sealed_data_t();
-    let sealed_ptr = &sealed_bytes as *const _ as *const u8;
-
     unsafe {
-        std::ptr::copy_nonoverlapping(sealed_ptr, sealed_out, sealed_size);
-        *sealed_len_out = sealed_size;
+        std::ptr::copy_nonoverlapping(sealed_bytes.as_ptr(), sealed_out, sealed_bytes.len());
+        *sealed_len_out = sealed_bytes.len();
     }
 
     sgx_status_t::SGX_SUCCESS
 }
 
-/// Unseal data that was previously sealed by this enclave (or same MRSIGNER).
+/// Unseal data that was previously sealed by this enclave (or same
+/// MRENCLAVE/MRSIGNER, depending on the policy it was sealed with - EGETKEY
+/// derives the matching unsealing key automatically from the blob itself).
+///
+/// Pass `check_rollback != 0` for a blob that was sealed with a monotonic
+/// counter binding (see [`ecall_seal_data`]); `rollback_detected_out` is
+/// then set to `1` and `SGX_ERROR_INVALID_PARAMETER` is returned if the
+/// blob's bound counter value is behind the counter's current value, i.e.
+/// the host fed back a stale copy of previously sealed state.
 #[no_mangle]
 pub extern "C" fn ecall_unseal_data(
     sealed: *const u8,
     sealed_len: usize,
+    check_rollback: i32,
+    rollback_detected_out: *mut i32,
     plaintext_out: *mut u8,
     plaintext_buf_len: usize,
     plaintext_len_out: *mut usize,
 ) -> sgx_status_t {
-    if sealed.is_null() || plaintext_out.is_null() || plaintext_len_out.is_null() {
-        return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+    if let Err(e) = marshal::check_out_ptr(plaintext_len_out) {
+        return e;
+    }
+    if let Err(e) = marshal::check_out_ptr_optional(rollback_detected_out) {
+        return e;
+    }
+    if !rollback_detected_out.is_null() {
+        unsafe { *rollback_detected_out = 0; }
     }
 
-    let sealed_slice = unsafe { std::slice::from_raw_parts(sealed, sealed_len) };
-
-    // Reconstruct sealed data structure
-    let sealed_data = match unsafe {
-        SgxSealedData::<[u8]>::from_raw_sealed_data_t(
-            sealed_slice.as_ptr() as *mut sgx_sealed_data_t,
-            sealed_len as u32,
-        )
-    } {
-        Some(sd) => sd,
-        None => return sgx_status_t::SGX_ERROR_INVALID_PARAMETER,
+    let sealed_slice = match marshal::copy_in_required(sealed, sealed_len) {
+        Ok(v) => v,
+        Err(e) => return e,
     };
 
-    // Unseal the data
-    let unsealed = match sealed_data.unseal_data() {
-        Ok(u) => u,
-        Err(e) => return e,
+    let (plaintext, aad) = match sealing::unseal_data(&sealed_slice) {
+        Ok(p) => p,
+        Err(_) => return sgx_status_t::SGX_ERROR_MAC_MISMATCH,
     };
 
-    let plaintext = unsealed.get_decrypt_txt();
+    if check_rollback != 0 {
+        let (binding, _) = match rollback::split_aad(&aad) {
+            Ok(b) => b,
+            Err(_) => return sgx_status_t::SGX_ERROR_INVALID_PARAMETER,
+        };
+        let current = match rollback::read_counter(&binding.uuid) {
+            Ok(v) => v,
+            Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
+        };
+        if binding.value < current {
+            if !rollback_detected_out.is_null() {
+                unsafe { *rollback_detected_out = 1; }
+            }
+            return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+        }
+    }
 
-    if plaintext_buf_len < plaintext.len() {
+    if let Err(e) = marshal::check_out_buf(plaintext_out, plaintext_buf_len, plaintext.len()) {
         unsafe { *plaintext_len_out = plaintext.len(); }
-        return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+        return e;
     }
 
     unsafe {
@@ -251,6 +514,126 @@ pub extern "C" fn ecall_unseal_data(
     sgx_status_t::SGX_SUCCESS
 }
 
+/// Reports the exact sealed-blob size the SDK will produce for the given
+/// plaintext/AAD lengths, so callers can size their output buffer precisely
+/// instead of guessing at the sealing overhead.
+#[no_mangle]
+pub extern "C" fn ecall_calc_sealed_size(
+    plaintext_len: usize,
+    aad_len: usize,
+    sealed_size_out: *mut usize,
+) -> sgx_status_t {
+    if let Err(e) = marshal::check_out_ptr(sealed_size_out) {
+        return e;
+    }
+    unsafe {
+        *sealed_size_out = sealing::calc_sealed_size(plaintext_len, aad_len);
+    }
+    sgx_status_t::SGX_SUCCESS
+}
+
+// =============================================================================
+// ECALL: Anti-Rollback Monotonic Counters
+// =============================================================================
+
+/// Creates a new SGX monotonic counter (initial value `0`) for binding
+/// sealed blobs against rollback. The counter's 16-byte UUID is written to
+/// `counter_uuid_out` and must be kept alongside the sealed blob (e.g. in
+/// its AAD) so a later unseal can look the counter back up.
+#[no_mangle]
+pub extern "C" fn ecall_create_monotonic_counter(
+    counter_uuid_out: *mut u8,
+    counter_value_out: *mut u32,
+) -> sgx_status_t {
+    if let Err(e) = marshal::check_out_buf(counter_uuid_out, 16, 16) {
+        return e;
+    }
+    if let Err(e) = marshal::check_out_ptr(counter_value_out) {
+        return e;
+    }
+
+    match rollback::create_counter() {
+        Ok((uuid, value)) => {
+            unsafe {
+                std::ptr::copy_nonoverlapping(uuid.as_ptr(), counter_uuid_out, 16);
+                *counter_value_out = value;
+            }
+            sgx_status_t::SGX_SUCCESS
+        }
+        Err(_) => sgx_status_t::SGX_ERROR_UNEXPECTED,
+    }
+}
+
+/// Reads the current value of a monotonic counter without incrementing it.
+#[no_mangle]
+pub extern "C" fn ecall_read_monotonic_counter(
+    counter_uuid: *const u8,
+    counter_value_out: *mut u32,
+) -> sgx_status_t {
+    let uuid_slice = match marshal::copy_in_required(counter_uuid, 16) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    if let Err(e) = marshal::check_out_ptr(counter_value_out) {
+        return e;
+    }
+    let mut uuid = [0u8; 16];
+    uuid.copy_from_slice(&uuid_slice);
+
+    match rollback::read_counter(&uuid) {
+        Ok(value) => {
+            unsafe { *counter_value_out = value; }
+            sgx_status_t::SGX_SUCCESS
+        }
+        Err(_) => sgx_status_t::SGX_ERROR_UNEXPECTED,
+    }
+}
+
+/// Increments a monotonic counter by one and returns its new value - call
+/// this each time the data bound to it (via [`ecall_seal_data`]'s
+/// `counter_uuid`) is re-sealed, so older copies become detectably stale.
+#[no_mangle]
+pub extern "C" fn ecall_increment_monotonic_counter(
+    counter_uuid: *const u8,
+    counter_value_out: *mut u32,
+) -> sgx_status_t {
+    let uuid_slice = match marshal::copy_in_required(counter_uuid, 16) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    if let Err(e) = marshal::check_out_ptr(counter_value_out) {
+        return e;
+    }
+    let mut uuid = [0u8; 16];
+    uuid.copy_from_slice(&uuid_slice);
+
+    match rollback::increment_counter(&uuid) {
+        Ok(value) => {
+            unsafe { *counter_value_out = value; }
+            sgx_status_t::SGX_SUCCESS
+        }
+        Err(_) => sgx_status_t::SGX_ERROR_UNEXPECTED,
+    }
+}
+
+/// Destroys a monotonic counter, releasing its slot in trusted storage.
+/// Once destroyed, any blob still bound to its UUID can no longer be
+/// rollback-checked.
+#[no_mangle]
+pub extern "C" fn ecall_destroy_monotonic_counter(counter_uuid: *const u8) -> sgx_status_t {
+    let uuid_slice = match marshal::copy_in_required(counter_uuid, 16) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let mut uuid = [0u8; 16];
+    uuid.copy_from_slice(&uuid_slice);
+
+    match rollback::destroy_counter(&uuid) {
+        Ok(()) => sgx_status_t::SGX_SUCCESS,
+        Err(_) => sgx_status_t::SGX_ERROR_UNEXPECTED,
+    }
+}
+
 // =============================================================================
 // ECALL: Remote Attestation
 // =============================================================================
@@ -264,17 +647,20 @@ pub extern "C" fn ecall_generate_report(
     target_info: *const sgx_target_info_t,
     report_out: *mut sgx_report_t,
 ) -> sgx_status_t {
-    if report_out.is_null() {
-        return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+    if let Err(e) = marshal::check_out_ptr(report_out) {
+        return e;
+    }
+    if let Err(e) = marshal::check_in_ptr(target_info) {
+        return e;
     }
 
     // Prepare report data (64 bytes max)
     let mut rd = sgx_report_data_t::default();
-    if !report_data.is_null() && report_data_len > 0 {
-        let len = std::cmp::min(report_data_len, 64);
-        let data_slice = unsafe { std::slice::from_raw_parts(report_data, len) };
-        rd.d[..len].copy_from_slice(data_slice);
-    }
+    let report_data_slice = match marshal::copy_in(report_data, std::cmp::min(report_data_len, 64)) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    rd.d[..report_data_slice.len()].copy_from_slice(&report_data_slice);
 
     // Get target info (use self if not provided)
     let ti = if target_info.is_null() {
@@ -298,67 +684,173 @@ pub extern "C" fn ecall_generate_report(
 // ECALL: Cryptographic Operations
 // =============================================================================
 
-/// Generate an ECDSA P-256 key pair inside the enclave.
+/// Generate an ECDSA P-256 key pair inside the enclave. `format` selects the
+/// returned public-key encoding (`0` = 65-byte uncompressed `04 || x || y`,
+/// `1` = 33-byte compressed `02/03 || x`); the key is always stored
+/// internally so it can be re-exported later in either form via
+/// [`ecall_export_public_key`] without regenerating it. `public_key_len_out`
+/// receives the number of bytes written - or, when `public_key_buf_len` is
+/// too small for the requested format, the required length, matching
+/// [`ecall_seal_data`]'s buffer-sizing convention.
 #[no_mangle]
 pub extern "C" fn ecall_generate_ecdsa_keypair(
     key_id: *const u8,
     key_id_len: usize,
+    format: u8,
     public_key_out: *mut u8,
-    public_key_len: usize,
+    public_key_buf_len: usize,
+    public_key_len_out: *mut usize,
 ) -> sgx_status_t {
-    if key_id.is_null() || public_key_out.is_null() || public_key_len < 65 {
-        return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+    let encoding = match public_key_encoding_from_wire(format) {
+        Ok(e) => e,
+        Err(e) => return e,
+    };
+    if let Err(e) = marshal::check_out_ptr(public_key_len_out) {
+        return e;
+    }
+    let required = types::KeyType::EcdsaP256.public_key_size_for(encoding);
+    if let Err(e) = marshal::check_out_buf(public_key_out, public_key_buf_len, required) {
+        unsafe { *public_key_len_out = required; }
+        return e;
     }
 
-    let key_id_str = match std::str::from_utf8(unsafe {
-        std::slice::from_raw_parts(key_id, key_id_len)
-    }) {
+    let key_id_bytes = match marshal::copy_in_required(key_id, key_id_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let key_id_str = match std::str::from_utf8(&key_id_bytes) {
         Ok(s) => String::from(s),
         Err(_) => return sgx_status_t::SGX_ERROR_INVALID_PARAMETER,
     };
 
-    // Generate ECDSA key pair
-    let mut private_key = sgx_ec256_private_t::default();
-    let mut public_key = sgx_ec256_public_t::default();
-
-    let ecc_handle = match SgxEccHandle::new() {
-        Ok(h) => h,
-        Err(e) => return e,
+    // Generate ECDSA key pair via `EcdsaKeyPair` rather than a bare
+    // `sgx_ec256_private_t` local, so the private key is zeroized on drop
+    // (see `crypto::Zeroizing`'s doc comment on why the SGX type can't be
+    // wrapped directly).
+    let keypair = match crypto::EcdsaKeyPair::generate() {
+        Ok(k) => k,
+        Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
     };
 
-    match ecc_handle.open() {
-        Ok(_) => {},
-        Err(e) => return e,
-    }
-
-    match ecc_handle.create_key_pair(&mut private_key, &mut public_key) {
-        Ok(_) => {},
-        Err(e) => return e,
-    }
-
     // Store key in enclave state
     let mut state = match ENCLAVE_STATE.lock() {
         Ok(s) => s,
         Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
     };
 
-    // Serialize public key (uncompressed format: 04 || x || y)
+    // Serialize public key (uncompressed format: 04 || x || y) - this is the
+    // canonical form kept in `state.keys`; export-time re-encoding derives
+    // the compressed form from it on demand.
     let mut pub_bytes = vec![0x04u8];
-    pub_bytes.extend_from_slice(&public_key.gx);
-    pub_bytes.extend_from_slice(&public_key.gy);
+    pub_bytes.extend_from_slice(&keypair.public_key.gx);
+    pub_bytes.extend_from_slice(&keypair.public_key.gy);
 
     // Serialize private key
-    let priv_bytes = private_key.r.to_vec();
+    let priv_bytes = keypair.private_key.r.to_vec();
 
     state.keys.insert(key_id_str, KeyEntry {
         key_type: KeyType::EcdsaP256,
-        private_key: priv_bytes,
-        public_key: pub_bytes.clone(),
+        private_key: crypto::Zeroizing::new(priv_bytes),
+        public_key: pub_bytes,
     });
 
+    let encoded = keypair.public_key_bytes_encoded(encoding);
+
     // Copy public key to output
     unsafe {
-        std::ptr::copy_nonoverlapping(pub_bytes.as_ptr(), public_key_out, pub_bytes.len());
+        std::ptr::copy_nonoverlapping(encoded.as_ptr(), public_key_out, encoded.len());
+        *public_key_len_out = encoded.len();
+    }
+
+    sgx_status_t::SGX_SUCCESS
+}
+
+/// Re-export an already-generated key's public key in either SEC1 encoding,
+/// without regenerating it. `format` is `0` for the 65-byte uncompressed
+/// point (`04 || x || y`) or `1` for the 33-byte compressed point
+/// (`02`/`03 || x`, prefix chosen by the parity of `y`). Works for both
+/// [`ecall_generate_ecdsa_keypair`]-generated P-256 keys and
+/// [`ecall_generate_secp256k1_keypair`]-generated secp256k1 keys; returns
+/// `SGX_ERROR_INVALID_PARAMETER` for a symmetric (AES-256) `key_id`, which
+/// has no public key to export. `public_key_len_out` receives the number of
+/// bytes written - or, when `public_key_buf_len` is too small, the required
+/// length, matching [`ecall_seal_data`]'s buffer-sizing convention.
+#[no_mangle]
+pub extern "C" fn ecall_export_public_key(
+    key_id: *const u8,
+    key_id_len: usize,
+    format: u8,
+    public_key_out: *mut u8,
+    public_key_buf_len: usize,
+    public_key_len_out: *mut usize,
+) -> sgx_status_t {
+    let encoding = match public_key_encoding_from_wire(format) {
+        Ok(e) => e,
+        Err(e) => return e,
+    };
+    if let Err(e) = marshal::check_out_ptr(public_key_len_out) {
+        return e;
+    }
+
+    let key_id_bytes = match marshal::copy_in_required(key_id, key_id_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let key_id_str = match std::str::from_utf8(&key_id_bytes) {
+        Ok(s) => s,
+        Err(_) => return sgx_status_t::SGX_ERROR_INVALID_PARAMETER,
+    };
+
+    let state = match ENCLAVE_STATE.lock() {
+        Ok(s) => s,
+        Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
+    };
+    let key_entry = match state.keys.get(key_id_str) {
+        Some(k) => k,
+        None => return sgx_status_t::SGX_ERROR_INVALID_PARAMETER,
+    };
+
+    let (key_type, encoded) = match key_entry.key_type {
+        KeyType::EcdsaP256 => {
+            // Canonical storage is always the 65-byte uncompressed point.
+            let stored = &key_entry.public_key;
+            let bytes = match encoding {
+                PublicKeyEncoding::Uncompressed => stored.clone(),
+                PublicKeyEncoding::Compressed => {
+                    let y_is_odd = stored[64] & 1 == 1;
+                    let mut out = Vec::with_capacity(33);
+                    out.push(if y_is_odd { 0x03 } else { 0x02 });
+                    out.extend_from_slice(&stored[1..33]);
+                    out
+                }
+            };
+            (types::KeyType::EcdsaP256, bytes)
+        }
+        KeyType::EcdsaSecp256k1 => {
+            // Canonical storage is the 33-byte compressed point.
+            let point = match secp256k1::decode_point(&key_entry.public_key) {
+                Ok(p) => p,
+                Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
+            };
+            let bytes = match encoding {
+                PublicKeyEncoding::Uncompressed => secp256k1::encode_uncompressed(&point).to_vec(),
+                PublicKeyEncoding::Compressed => secp256k1::encode_compressed(&point).to_vec(),
+            };
+            (types::KeyType::EcdsaSecp256k1, bytes)
+        }
+        KeyType::Aes256 => return sgx_status_t::SGX_ERROR_INVALID_PARAMETER,
+    };
+    drop(state);
+
+    let required = key_type.public_key_size_for(encoding);
+    if let Err(e) = marshal::check_out_buf(public_key_out, public_key_buf_len, required) {
+        unsafe { *public_key_len_out = required; }
+        return e;
+    }
+
+    unsafe {
+        std::ptr::copy_nonoverlapping(encoded.as_ptr(), public_key_out, encoded.len());
+        *public_key_len_out = encoded.len();
     }
 
     sgx_status_t::SGX_SUCCESS
@@ -374,18 +866,23 @@ pub extern "C" fn ecall_ecdsa_sign(
     signature_out: *mut u8,
     signature_len: usize,
 ) -> sgx_status_t {
-    if key_id.is_null() || data.is_null() || signature_out.is_null() || signature_len < 64 {
-        return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+    if let Err(e) = marshal::check_out_buf(signature_out, signature_len, 64) {
+        return e;
     }
 
-    let key_id_str = match std::str::from_utf8(unsafe {
-        std::slice::from_raw_parts(key_id, key_id_len)
-    }) {
+    let key_id_bytes = match marshal::copy_in_required(key_id, key_id_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let key_id_str = match std::str::from_utf8(&key_id_bytes) {
         Ok(s) => s,
         Err(_) => return sgx_status_t::SGX_ERROR_INVALID_PARAMETER,
     };
 
-    let data_slice = unsafe { std::slice::from_raw_parts(data, data_len) };
+    let data_slice = match marshal::copy_in_required(data, data_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
 
     // Get key from state
     let state = match ENCLAVE_STATE.lock() {
@@ -398,12 +895,18 @@ pub extern "C" fn ecall_ecdsa_sign(
         None => return sgx_status_t::SGX_ERROR_INVALID_PARAMETER,
     };
 
-    // Reconstruct private key
-    let mut private_key = sgx_ec256_private_t::default();
-    private_key.r.copy_from_slice(&key_entry.private_key);
+    // Reconstruct the private key via `EcdsaKeyPair` rather than a bare
+    // `sgx_ec256_private_t` local: the SGX type is foreign and can't be
+    // wrapped in `Zeroizing` directly (see `crypto::Zeroizing`'s doc
+    // comment), but `EcdsaKeyPair` already zeroizes `private_key.r` on
+    // drop, so this gets the same protection on every exit path below.
+    let keypair = match crypto::EcdsaKeyPair::from_private_key(&key_entry.private_key) {
+        Ok(k) => k,
+        Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
+    };
 
     // Hash the data first (SHA-256)
-    let hash = match rsgx_sha256_slice(data_slice) {
+    let hash = match rsgx_sha256_slice(&data_slice) {
         Ok(h) => h,
         Err(e) => return e,
     };
@@ -419,7 +922,7 @@ pub extern "C" fn ecall_ecdsa_sign(
         Err(e) => return e,
     }
 
-    let signature = match ecc_handle.ecdsa_sign_slice(&hash, &private_key) {
+    let signature = match ecc_handle.ecdsa_sign_slice(&hash, &keypair.private_key) {
         Ok(s) => s,
         Err(e) => return e,
     };
@@ -436,65 +939,436 @@ pub extern "C" fn ecall_ecdsa_sign(
     sgx_status_t::SGX_SUCCESS
 }
 
-/// Compute SHA-256 hash.
+/// Fill a buffer with bytes from the enclave's hardware CSPRNG
+/// (`sgx_read_rand`, RDRAND-backed). Randomness used for key material, IVs,
+/// or nonces must never be generated in untrusted host memory.
 #[no_mangle]
-pub extern "C" fn ecall_sha256(
-    data: *const u8,
-    data_len: usize,
-    hash_out: *mut u8,
-    hash_len: usize,
+pub extern "C" fn ecall_random_bytes(
+    buffer_out: *mut u8,
+    length: usize,
 ) -> sgx_status_t {
-    if data.is_null() || hash_out.is_null() || hash_len < 32 {
+    if let Err(e) = marshal::check_out_buf(buffer_out, length, length) {
+        return e;
+    }
+    if length == 0 {
         return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
     }
 
-    let data_slice = unsafe { std::slice::from_raw_parts(data, data_len) };
-
-    let hash = match rsgx_sha256_slice(data_slice) {
-        Ok(h) => h,
+    let mut buf = vec![0u8; length];
+    match rsgx_read_rand(&mut buf) {
+        Ok(_) => {}
         Err(e) => return e,
-    };
+    }
 
     unsafe {
-        std::ptr::copy_nonoverlapping(hash.as_ptr(), hash_out, 32);
+        std::ptr::copy_nonoverlapping(buf.as_ptr(), buffer_out, length);
     }
 
     sgx_status_t::SGX_SUCCESS
 }
 
-/// AES-256-GCM encryption inside the enclave.
+/// Verify an ECDSA P-256 signature using the SGX crypto library.
 #[no_mangle]
-pub extern "C" fn ecall_aes_gcm_encrypt(
-    key: *const u8,
-    key_len: usize,
-    iv: *const u8,
-    iv_len: usize,
-    plaintext: *const u8,
-    plaintext_len: usize,
-    aad: *const u8,
-    aad_len: usize,
-    ciphertext_out: *mut u8,
-    ciphertext_len: usize,
-    tag_out: *mut u8,
-    tag_len: usize,
+pub extern "C" fn ecall_ecdsa_verify(
+    public_key: *const u8,
+    public_key_len: usize,
+    data: *const u8,
+    data_len: usize,
+    signature: *const u8,
+    signature_len: usize,
+    valid_out: *mut i32,
 ) -> sgx_status_t {
-    if key.is_null() || key_len != 32 || iv.is_null() || iv_len != 12
-        || plaintext.is_null() || ciphertext_out.is_null()
-        || ciphertext_len < plaintext_len || tag_out.is_null() || tag_len < 16 {
+    if public_key_len != 65 || signature_len != 64 {
         return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
     }
+    if let Err(e) = marshal::check_out_ptr(valid_out) {
+        return e;
+    }
 
-    let key_slice = unsafe { std::slice::from_raw_parts(key, key_len) };
-    let iv_slice = unsafe { std::slice::from_raw_parts(iv, iv_len) };
-    let plaintext_slice = unsafe { std::slice::from_raw_parts(plaintext, plaintext_len) };
-
-    let aad_slice = if aad.is_null() || aad_len == 0 {
-        &[]
-    } else {
-        unsafe { std::slice::from_raw_parts(aad, aad_len) }
+    let pub_slice = match marshal::copy_in_required(public_key, public_key_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let data_slice = match marshal::copy_in_required(data, data_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let sig_slice = match marshal::copy_in_required(signature, signature_len) {
+        Ok(v) => v,
+        Err(e) => return e,
     };
 
-    // Prepare key
+    // Uncompressed SEC1: 04 || x || y.
+    if pub_slice[0] != 0x04 {
+        return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+    }
+    let mut public_key_t = sgx_ec256_public_t::default();
+    public_key_t.gx.copy_from_slice(&pub_slice[1..33]);
+    public_key_t.gy.copy_from_slice(&pub_slice[33..65]);
+
+    let mut signature_t = sgx_ec256_signature_t::default();
+    signature_t.x.copy_from_slice(&sig_slice[..32]);
+    signature_t.y.copy_from_slice(&sig_slice[32..64]);
+
+    let hash = match rsgx_sha256_slice(&data_slice) {
+        Ok(h) => h,
+        Err(e) => return e,
+    };
+
+    let ecc_handle = match SgxEccHandle::new() {
+        Ok(h) => h,
+        Err(e) => return e,
+    };
+    match ecc_handle.open() {
+        Ok(_) => {}
+        Err(e) => return e,
+    }
+
+    let valid = match ecc_handle.ecdsa_verify_slice(&hash, &public_key_t, &signature_t) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    unsafe {
+        *valid_out = if valid { 1 } else { 0 };
+    }
+
+    sgx_status_t::SGX_SUCCESS
+}
+
+/// Generate a secp256k1 key pair inside the enclave for blockchain-facing
+/// (Neo/Ethereum-style) signing, returning the 33-byte compressed public key.
+#[no_mangle]
+pub extern "C" fn ecall_generate_secp256k1_keypair(
+    key_id: *const u8,
+    key_id_len: usize,
+    public_key_out: *mut u8,
+    public_key_len: usize,
+) -> sgx_status_t {
+    if let Err(e) = marshal::check_out_buf(public_key_out, public_key_len, 33) {
+        return e;
+    }
+
+    let key_id_bytes = match marshal::copy_in_required(key_id, key_id_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let key_id_str = match std::str::from_utf8(&key_id_bytes) {
+        Ok(s) => String::from(s),
+        Err(_) => return sgx_status_t::SGX_ERROR_INVALID_PARAMETER,
+    };
+
+    let keypair = match crypto::Secp256k1KeyPair::generate() {
+        Ok(k) => k,
+        Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
+    };
+
+    let compressed = keypair.public_key_bytes_encoded(PublicKeyEncoding::Compressed);
+
+    let mut state = match ENCLAVE_STATE.lock() {
+        Ok(s) => s,
+        Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
+    };
+
+    state.keys.insert(key_id_str, KeyEntry {
+        key_type: KeyType::EcdsaSecp256k1,
+        private_key: crypto::Zeroizing::new(keypair.private_key_bytes().to_vec()),
+        public_key: compressed.clone(),
+    });
+
+    unsafe {
+        std::ptr::copy_nonoverlapping(compressed.as_ptr(), public_key_out, compressed.len());
+    }
+
+    sgx_status_t::SGX_SUCCESS
+}
+
+/// Sign data with a stored secp256k1 key, producing a 64-byte compact
+/// `r || s` signature (no recovery id) for Neo-style verification.
+#[no_mangle]
+pub extern "C" fn ecall_secp256k1_sign(
+    key_id: *const u8,
+    key_id_len: usize,
+    data: *const u8,
+    data_len: usize,
+    signature_out: *mut u8,
+    signature_len: usize,
+) -> sgx_status_t {
+    if let Err(e) = marshal::check_out_buf(signature_out, signature_len, 64) {
+        return e;
+    }
+
+    let key_id_bytes = match marshal::copy_in_required(key_id, key_id_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let key_id_str = match std::str::from_utf8(&key_id_bytes) {
+        Ok(s) => s,
+        Err(_) => return sgx_status_t::SGX_ERROR_INVALID_PARAMETER,
+    };
+
+    let data_slice = match marshal::copy_in_required(data, data_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    let state = match ENCLAVE_STATE.lock() {
+        Ok(s) => s,
+        Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
+    };
+
+    let key_entry = match state.keys.get(key_id_str) {
+        Some(k) => k,
+        None => return sgx_status_t::SGX_ERROR_INVALID_PARAMETER,
+    };
+
+    let mut private_key = crypto::Zeroizing::new([0u8; 32]);
+    private_key.copy_from_slice(&key_entry.private_key);
+
+    let hash = match rsgx_sha256_slice(&data_slice) {
+        Ok(h) => h,
+        Err(e) => return e,
+    };
+
+    let keypair = match crypto::Secp256k1KeyPair::from_private_key(&private_key) {
+        Ok(k) => k,
+        Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
+    };
+
+    let signature = match keypair.sign(&hash) {
+        Ok(s) => s,
+        Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
+    };
+
+    unsafe {
+        std::ptr::copy_nonoverlapping(signature.as_ptr(), signature_out, 64);
+    }
+
+    sgx_status_t::SGX_SUCCESS
+}
+
+/// Verify a 64-byte compact secp256k1 signature against a public key
+/// (33-byte compressed or 65-byte uncompressed SEC1 form).
+#[no_mangle]
+pub extern "C" fn ecall_secp256k1_verify(
+    public_key: *const u8,
+    public_key_len: usize,
+    data: *const u8,
+    data_len: usize,
+    signature: *const u8,
+    signature_len: usize,
+    valid_out: *mut i32,
+) -> sgx_status_t {
+    if signature_len != 64 {
+        return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+    }
+    if let Err(e) = marshal::check_out_ptr(valid_out) {
+        return e;
+    }
+
+    let pub_slice = match marshal::copy_in_required(public_key, public_key_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let data_slice = match marshal::copy_in_required(data, data_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let sig_slice = match marshal::copy_in_required(signature, signature_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    let hash = match rsgx_sha256_slice(&data_slice) {
+        Ok(h) => h,
+        Err(e) => return e,
+    };
+
+    let mut signature_bytes = [0u8; 64];
+    signature_bytes.copy_from_slice(&sig_slice);
+
+    let valid = crypto::Secp256k1KeyPair::verify(&pub_slice, &hash, &signature_bytes).unwrap_or(false);
+
+    unsafe {
+        *valid_out = if valid { 1 } else { 0 };
+    }
+
+    sgx_status_t::SGX_SUCCESS
+}
+
+/// Compute an ECDH shared secret between a stored key and a peer's public
+/// key, returning SHA-256 of the shared point's x-coordinate (32 bytes) -
+/// suitable for feeding directly to [`ecall_aes_gcm_encrypt`] as a session
+/// key. Dispatches on the stored key's type: P-256 keys go through
+/// [`crypto::EcdsaKeyPair::ecdh`] (`SgxEccHandle::compute_shared_dhkey`,
+/// uncompressed peer key only), secp256k1 keys through
+/// [`crypto::Secp256k1KeyPair::ecdh`] (compressed or uncompressed).
+#[no_mangle]
+pub extern "C" fn ecall_ecdh(
+    key_id: *const u8,
+    key_id_len: usize,
+    peer_public_key: *const u8,
+    peer_public_key_len: usize,
+    shared_out: *mut u8,
+    shared_len: usize,
+) -> sgx_status_t {
+    if let Err(e) = marshal::check_out_buf(shared_out, shared_len, 32) {
+        return e;
+    }
+
+    let key_id_bytes = match marshal::copy_in_required(key_id, key_id_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let key_id_str = match std::str::from_utf8(&key_id_bytes) {
+        Ok(s) => s,
+        Err(_) => return sgx_status_t::SGX_ERROR_INVALID_PARAMETER,
+    };
+    let peer_key_slice = match marshal::copy_in_required(peer_public_key, peer_public_key_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    let state = match ENCLAVE_STATE.lock() {
+        Ok(s) => s,
+        Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
+    };
+
+    let key_entry = match state.keys.get(key_id_str) {
+        Some(k) => k,
+        None => return sgx_status_t::SGX_ERROR_INVALID_PARAMETER,
+    };
+
+    let shared_secret = match key_entry.key_type {
+        KeyType::EcdsaP256 => {
+            let mut ecdsa = crypto::EcdsaKeyPair {
+                private_key: sgx_ec256_private_t::default(),
+                public_key: sgx_ec256_public_t::default(),
+            };
+            ecdsa.private_key.r.copy_from_slice(&key_entry.private_key);
+            match ecdsa.ecdh(&peer_key_slice) {
+                Ok(s) => s,
+                Err(_) => return sgx_status_t::SGX_ERROR_INVALID_PARAMETER,
+            }
+        }
+        KeyType::EcdsaSecp256k1 => {
+            let mut private_key = crypto::Zeroizing::new([0u8; 32]);
+            private_key.copy_from_slice(&key_entry.private_key);
+            let keypair = match crypto::Secp256k1KeyPair::from_private_key(&private_key) {
+                Ok(k) => k,
+                Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
+            };
+            match keypair.ecdh(&peer_key_slice) {
+                Ok(s) => s,
+                Err(_) => return sgx_status_t::SGX_ERROR_INVALID_PARAMETER,
+            }
+        }
+        KeyType::Aes256 => return sgx_status_t::SGX_ERROR_INVALID_PARAMETER,
+    };
+
+    unsafe {
+        std::ptr::copy_nonoverlapping(shared_secret.as_ptr(), shared_out, 32);
+    }
+
+    sgx_status_t::SGX_SUCCESS
+}
+
+/// Compute SHA-256 hash.
+#[no_mangle]
+pub extern "C" fn ecall_sha256(
+    data: *const u8,
+    data_len: usize,
+    hash_out: *mut u8,
+    hash_len: usize,
+) -> sgx_status_t {
+    if let Err(e) = marshal::check_out_buf(hash_out, hash_len, 32) {
+        return e;
+    }
+
+    let data_slice = match marshal::copy_in_required(data, data_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    let hash = match rsgx_sha256_slice(&data_slice) {
+        Ok(h) => h,
+        Err(e) => return e,
+    };
+
+    unsafe {
+        std::ptr::copy_nonoverlapping(hash.as_ptr(), hash_out, 32);
+    }
+
+    sgx_status_t::SGX_SUCCESS
+}
+
+/// AES-256-GCM encryption inside the enclave.
+///
+/// If `generate_iv` is non-zero, a fresh 12-byte IV is drawn from
+/// `sgx_read_rand` inside the enclave and written back through `iv` before
+/// encrypting, rather than trusting a caller-supplied IV that an untrusted
+/// host could replay across calls and break GCM's confidentiality guarantee.
+#[no_mangle]
+pub extern "C" fn ecall_aes_gcm_encrypt(
+    key: *const u8,
+    key_len: usize,
+    iv: *mut u8,
+    iv_len: usize,
+    generate_iv: i32,
+    plaintext: *const u8,
+    plaintext_len: usize,
+    aad: *const u8,
+    aad_len: usize,
+    ciphertext_out: *mut u8,
+    ciphertext_len: usize,
+    tag_out: *mut u8,
+    tag_len: usize,
+) -> sgx_status_t {
+    if key_len != 32 || iv_len != 12 {
+        return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+    }
+    // `iv` is written back into when `generate_iv` is set, so it needs the
+    // same outside-enclave check as any other output buffer before we touch
+    // it - not just the `copy_in_required` read further down.
+    if let Err(e) = marshal::check_out_buf(iv, iv_len, 12) {
+        return e;
+    }
+    if let Err(e) = marshal::check_out_buf(ciphertext_out, ciphertext_len, plaintext_len) {
+        return e;
+    }
+    if let Err(e) = marshal::check_out_buf(tag_out, tag_len, 16) {
+        return e;
+    }
+
+    if generate_iv != 0 {
+        let mut fresh_iv = [0u8; 12];
+        match rsgx_read_rand(&mut fresh_iv) {
+            Ok(_) => {}
+            Err(e) => return e,
+        }
+        unsafe {
+            std::ptr::copy_nonoverlapping(fresh_iv.as_ptr(), iv, 12);
+        }
+    }
+
+    let key_slice = match marshal::copy_in_required(key, key_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let iv_slice = match marshal::copy_in_required(iv as *const u8, iv_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let plaintext_slice = match marshal::copy_in_required(plaintext, plaintext_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let aad_slice = match marshal::copy_in(aad, aad_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    // Prepare key
     let mut aes_key = sgx_aes_gcm_128bit_key_t::default();
     aes_key.copy_from_slice(&key_slice[..16]); // Use first 128 bits for SGX API
 
@@ -504,9 +1378,9 @@ pub extern "C" fn ecall_aes_gcm_encrypt(
 
     match rsgx_rijndael128GCM_encrypt(
         &aes_key,
-        plaintext_slice,
-        iv_slice,
-        aad_slice,
+        &plaintext_slice,
+        &iv_slice,
+        &aad_slice,
         &mut ciphertext,
         &mut tag,
     ) {
@@ -538,21 +1412,32 @@ pub extern "C" fn ecall_aes_gcm_decrypt(
     plaintext_out: *mut u8,
     plaintext_buf_len: usize,
 ) -> sgx_status_t {
-    if key.is_null() || key_len != 32 || iv.is_null() || iv_len != 12
-        || ciphertext.is_null() || tag.is_null() || tag_len != 16
-        || plaintext_out.is_null() || plaintext_buf_len < ciphertext_len {
+    if key_len != 32 || iv_len != 12 || tag_len != 16 {
         return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
     }
+    if let Err(e) = marshal::check_out_buf(plaintext_out, plaintext_buf_len, ciphertext_len) {
+        return e;
+    }
 
-    let key_slice = unsafe { std::slice::from_raw_parts(key, key_len) };
-    let iv_slice = unsafe { std::slice::from_raw_parts(iv, iv_len) };
-    let ciphertext_slice = unsafe { std::slice::from_raw_parts(ciphertext, ciphertext_len) };
-    let tag_slice = unsafe { std::slice::from_raw_parts(tag, tag_len) };
-
-    let aad_slice = if aad.is_null() || aad_len == 0 {
-        &[]
-    } else {
-        unsafe { std::slice::from_raw_parts(aad, aad_len) }
+    let key_slice = match marshal::copy_in_required(key, key_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let iv_slice = match marshal::copy_in_required(iv, iv_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let ciphertext_slice = match marshal::copy_in_required(ciphertext, ciphertext_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let tag_slice = match marshal::copy_in_required(tag, tag_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let aad_slice = match marshal::copy_in(aad, aad_len) {
+        Ok(v) => v,
+        Err(e) => return e,
     };
 
     // Prepare key and tag
@@ -560,16 +1445,16 @@ pub extern "C" fn ecall_aes_gcm_decrypt(
     aes_key.copy_from_slice(&key_slice[..16]);
 
     let mut aes_tag = sgx_aes_gcm_128bit_tag_t::default();
-    aes_tag.copy_from_slice(tag_slice);
+    aes_tag.copy_from_slice(&tag_slice);
 
     // Decrypt
     let mut plaintext = vec![0u8; ciphertext_len];
 
     match rsgx_rijndael128GCM_decrypt(
         &aes_key,
-        ciphertext_slice,
-        iv_slice,
-        aad_slice,
+        &ciphertext_slice,
+        &iv_slice,
+        &aad_slice,
         &aes_tag,
         &mut plaintext,
     ) {
@@ -585,17 +1470,836 @@ pub extern "C" fn ecall_aes_gcm_decrypt(
 }
 
 // =============================================================================
-// ECALL: Get Enclave Info
+// ECALL: Local Attestation & Enclave-to-Enclave Session Establishment
 // =============================================================================
 
-/// Get enclave measurement (MRENCLAVE) and signer (MRSIGNER).
+/// Export this enclave's target info, so a peer enclave can target its
+/// `EREPORT` at us as the first step of a mutual local-attestation handshake.
 #[no_mangle]
-pub extern "C" fn ecall_get_enclave_info(
-    mr_enclave_out: *mut u8,
-    mr_signer_out: *mut u8,
+pub extern "C" fn ecall_get_target_info(
+    target_info_out: *mut sgx_target_info_t,
 ) -> sgx_status_t {
-    if mr_enclave_out.is_null() || mr_signer_out.is_null() {
-        return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+    if let Err(e) = marshal::check_out_ptr(target_info_out) {
+        return e;
+    }
+
+    let report = match rsgx_self_report() {
+        Ok(r) => r,
+        Err(e) => return e,
+    };
+
+    let mut ti = sgx_target_info_t::default();
+    ti.mr_enclave = report.body.mr_enclave;
+    ti.attributes = report.body.attributes;
+    ti.misc_select = report.body.misc_select;
+
+    unsafe { *target_info_out = ti; }
+
+    sgx_status_t::SGX_SUCCESS
+}
+
+/// Verify a peer enclave's local-attestation report (EREPORT MAC check via
+/// EGETKEY). Does not by itself check MRENCLAVE/MRSIGNER - callers that care
+/// which enclave they're talking to still need to inspect `report.body`.
+#[no_mangle]
+pub extern "C" fn ecall_verify_report(
+    report: *const sgx_report_t,
+    valid_out: *mut i32,
+) -> sgx_status_t {
+    if report.is_null() {
+        return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+    }
+    if let Err(e) = marshal::check_in_ptr(report) {
+        return e;
+    }
+    if let Err(e) = marshal::check_out_ptr(valid_out) {
+        return e;
+    }
+
+    let report_val = unsafe { *report };
+    match attestation::verify_report(&report_val) {
+        Ok(valid) => {
+            unsafe { *valid_out = if valid { 1 } else { 0 }; }
+            sgx_status_t::SGX_SUCCESS
+        }
+        Err(_) => sgx_status_t::SGX_ERROR_UNEXPECTED,
+    }
+}
+
+/// Begin a SIGMA-style mutual key exchange with a peer enclave. Generates an
+/// ephemeral P-256 key pair, binds its public key into a report targeted at
+/// the peer (so the peer's report MAC check also authenticates the key), and
+/// stashes the ephemeral private key under `session_id` until
+/// [`ecall_session_complete`] consumes it. The caller is expected to run this
+/// same handshake symmetrically on both enclaves and swap the resulting
+/// public keys and reports.
+#[no_mangle]
+pub extern "C" fn ecall_session_init(
+    session_id: *const u8,
+    session_id_len: usize,
+    peer_target_info: *const sgx_target_info_t,
+    public_key_out: *mut u8,
+    public_key_len: usize,
+    report_out: *mut sgx_report_t,
+) -> sgx_status_t {
+    if peer_target_info.is_null() {
+        return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+    }
+    if let Err(e) = marshal::check_in_ptr(peer_target_info) {
+        return e;
+    }
+    if let Err(e) = marshal::check_out_ptr(report_out) {
+        return e;
+    }
+    if let Err(e) = marshal::check_out_buf(public_key_out, public_key_len, 65) {
+        return e;
+    }
+
+    let session_id_bytes = match marshal::copy_in_required(session_id, session_id_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let session_id_str = match std::str::from_utf8(&session_id_bytes) {
+        Ok(s) => String::from(s),
+        Err(_) => return sgx_status_t::SGX_ERROR_INVALID_PARAMETER,
+    };
+
+    let ephemeral = match crypto::EcdsaKeyPair::generate() {
+        Ok(k) => k,
+        Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
+    };
+    let public_key_bytes = ephemeral.public_key_bytes();
+
+    // Bind the ephemeral public key into the report data so the peer's
+    // EREPORT MAC check also vouches for this exact key, not just "some
+    // report from this enclave".
+    let report_data = match crypto::sha256(&public_key_bytes) {
+        Ok(h) => h,
+        Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
+    };
+
+    let ti = unsafe { *peer_target_info };
+    let report = match attestation::generate_report(Some(&ti), &report_data) {
+        Ok(r) => r,
+        Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
+    };
+
+    let mut state = match ENCLAVE_STATE.lock() {
+        Ok(s) => s,
+        Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
+    };
+    let mut priv_bytes = crypto::Zeroizing::new([0u8; 32]);
+    priv_bytes.copy_from_slice(&ephemeral.private_key_bytes());
+    state.sessions.insert(session_id_str, priv_bytes);
+
+    unsafe {
+        std::ptr::copy_nonoverlapping(public_key_bytes.as_ptr(), public_key_out, 65);
+        *report_out = report;
+    }
+
+    sgx_status_t::SGX_SUCCESS
+}
+
+/// Complete a SIGMA-style session: verify the peer's report, check it is
+/// bound to the peer's claimed ephemeral public key, optionally pin the
+/// peer's MRENCLAVE/MRSIGNER, then derive a shared AES-256-GCM session key
+/// via ECDH + HKDF and store it under `key_id`. The session key never leaves
+/// the enclave - use [`ecall_session_encrypt`]/[`ecall_session_decrypt`] to
+/// migrate sealed secrets to the peer rather than exporting it.
+///
+/// `expected_mr_enclave`/`expected_mr_signer` are optional 32-byte pins;
+/// pass null to skip either check.
+#[no_mangle]
+pub extern "C" fn ecall_session_complete(
+    session_id: *const u8,
+    session_id_len: usize,
+    peer_public_key: *const u8,
+    peer_public_key_len: usize,
+    peer_report: *const sgx_report_t,
+    expected_mr_enclave: *const u8,
+    expected_mr_signer: *const u8,
+    key_id: *const u8,
+    key_id_len: usize,
+) -> sgx_status_t {
+    if peer_report.is_null() {
+        return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+    }
+    if let Err(e) = marshal::check_in_ptr(peer_report) {
+        return e;
+    }
+    // `expected_mr_enclave`/`expected_mr_signer` are validated below via
+    // `copy_in_required` once we know they're non-null, since their 32-byte
+    // extent isn't implied by their `*const u8` type the way `check_in_ptr`
+    // assumes.
+
+    let session_id_bytes = match marshal::copy_in_required(session_id, session_id_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let session_id_str = match std::str::from_utf8(&session_id_bytes) {
+        Ok(s) => String::from(s),
+        Err(_) => return sgx_status_t::SGX_ERROR_INVALID_PARAMETER,
+    };
+
+    let peer_pub_slice = match marshal::copy_in_required(peer_public_key, peer_public_key_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    if peer_pub_slice.len() != 65 || peer_pub_slice[0] != 0x04 {
+        return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+    }
+
+    let key_id_bytes = match marshal::copy_in_required(key_id, key_id_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let key_id_str = match std::str::from_utf8(&key_id_bytes) {
+        Ok(s) => String::from(s),
+        Err(_) => return sgx_status_t::SGX_ERROR_INVALID_PARAMETER,
+    };
+
+    let report = unsafe { *peer_report };
+
+    match attestation::verify_report(&report) {
+        Ok(true) => {}
+        Ok(false) => return sgx_status_t::SGX_ERROR_MAC_MISMATCH,
+        Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
+    }
+
+    let expected_hash = match crypto::sha256(&peer_pub_slice) {
+        Ok(h) => h,
+        Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
+    };
+    if report.body.report_data.d[..32] != expected_hash[..] {
+        return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+    }
+
+    if !expected_mr_enclave.is_null() {
+        let want = match marshal::copy_in_required(expected_mr_enclave, 32) {
+            Ok(v) => v,
+            Err(e) => return e,
+        };
+        if report.body.mr_enclave.m[..] != want[..] {
+            return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+        }
+    }
+    if !expected_mr_signer.is_null() {
+        let want = match marshal::copy_in_required(expected_mr_signer, 32) {
+            Ok(v) => v,
+            Err(e) => return e,
+        };
+        if report.body.mr_signer.m[..] != want[..] {
+            return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+        }
+    }
+
+    let mut state = match ENCLAVE_STATE.lock() {
+        Ok(s) => s,
+        Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
+    };
+
+    let ephemeral_private = match state.sessions.remove(&session_id_str) {
+        Some(k) => k,
+        None => return sgx_status_t::SGX_ERROR_INVALID_PARAMETER,
+    };
+
+    let mut ephemeral = crypto::EcdsaKeyPair {
+        private_key: sgx_ec256_private_t::default(),
+        public_key: sgx_ec256_public_t::default(),
+    };
+    ephemeral.private_key.r.copy_from_slice(&ephemeral_private);
+
+    let shared_secret = match ephemeral.ecdh(&peer_pub_slice) {
+        Ok(s) => s,
+        Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
+    };
+
+    let session_key = match crypto::hkdf_sha256(
+        &shared_secret,
+        b"",
+        b"sgx-local-attestation-session-key",
+        32,
+    ) {
+        Ok(k) => k,
+        Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
+    };
+
+    state.keys.insert(key_id_str, KeyEntry {
+        key_type: KeyType::Aes256,
+        private_key: crypto::Zeroizing::new(session_key),
+        public_key: Vec::new(),
+    });
+
+    sgx_status_t::SGX_SUCCESS
+}
+
+/// Encrypt data with a session key established by [`ecall_session_complete`],
+/// keeping the shared secret inside the enclave instead of exporting it to
+/// the untrusted host for use with the raw `ecall_aes_gcm_encrypt`.
+#[no_mangle]
+pub extern "C" fn ecall_session_encrypt(
+    key_id: *const u8,
+    key_id_len: usize,
+    plaintext: *const u8,
+    plaintext_len: usize,
+    aad: *const u8,
+    aad_len: usize,
+    ciphertext_out: *mut u8,
+    ciphertext_len: usize,
+    nonce_out: *mut u8,
+    nonce_len: usize,
+    tag_out: *mut u8,
+    tag_len: usize,
+) -> sgx_status_t {
+    if let Err(e) = marshal::check_out_buf(ciphertext_out, ciphertext_len, plaintext_len) {
+        return e;
+    }
+    if let Err(e) = marshal::check_out_buf(nonce_out, nonce_len, 12) {
+        return e;
+    }
+    if let Err(e) = marshal::check_out_buf(tag_out, tag_len, 16) {
+        return e;
+    }
+
+    let key_id_bytes = match marshal::copy_in_required(key_id, key_id_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let key_id_str = match std::str::from_utf8(&key_id_bytes) {
+        Ok(s) => s,
+        Err(_) => return sgx_status_t::SGX_ERROR_INVALID_PARAMETER,
+    };
+    let plaintext_slice = match marshal::copy_in_required(plaintext, plaintext_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let aad_slice = match marshal::copy_in(aad, aad_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    let state = match ENCLAVE_STATE.lock() {
+        Ok(s) => s,
+        Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
+    };
+    let key_entry = match state.keys.get(key_id_str) {
+        Some(k) if matches!(k.key_type, KeyType::Aes256) => k,
+        _ => return sgx_status_t::SGX_ERROR_INVALID_PARAMETER,
+    };
+    let mut key = crypto::Zeroizing::new([0u8; 32]);
+    key.copy_from_slice(&key_entry.private_key);
+    drop(state);
+
+    let mut nonce = [0u8; 12];
+    match rsgx_read_rand(&mut nonce) {
+        Ok(_) => {}
+        Err(e) => return e,
+    }
+
+    let (ciphertext, tag) = match crypto::AesGcm::encrypt(&key, &nonce, &plaintext_slice, &aad_slice) {
+        Ok(r) => r,
+        Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
+    };
+
+    unsafe {
+        std::ptr::copy_nonoverlapping(ciphertext.as_ptr(), ciphertext_out, ciphertext.len());
+        std::ptr::copy_nonoverlapping(nonce.as_ptr(), nonce_out, 12);
+        std::ptr::copy_nonoverlapping(tag.as_ptr(), tag_out, 16);
+    }
+
+    sgx_status_t::SGX_SUCCESS
+}
+
+/// Decrypt data with a session key established by [`ecall_session_complete`].
+#[no_mangle]
+pub extern "C" fn ecall_session_decrypt(
+    key_id: *const u8,
+    key_id_len: usize,
+    ciphertext: *const u8,
+    ciphertext_len: usize,
+    aad: *const u8,
+    aad_len: usize,
+    nonce: *const u8,
+    nonce_len: usize,
+    tag: *const u8,
+    tag_len: usize,
+    plaintext_out: *mut u8,
+    plaintext_buf_len: usize,
+) -> sgx_status_t {
+    if nonce_len != 12 || tag_len != 16 {
+        return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+    }
+    if let Err(e) = marshal::check_out_buf(plaintext_out, plaintext_buf_len, ciphertext_len) {
+        return e;
+    }
+
+    let key_id_bytes = match marshal::copy_in_required(key_id, key_id_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let key_id_str = match std::str::from_utf8(&key_id_bytes) {
+        Ok(s) => s,
+        Err(_) => return sgx_status_t::SGX_ERROR_INVALID_PARAMETER,
+    };
+    let ciphertext_slice = match marshal::copy_in_required(ciphertext, ciphertext_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let aad_slice = match marshal::copy_in(aad, aad_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let nonce_slice = match marshal::copy_in_required(nonce, nonce_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let tag_slice = match marshal::copy_in_required(tag, tag_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    let state = match ENCLAVE_STATE.lock() {
+        Ok(s) => s,
+        Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
+    };
+    let key_entry = match state.keys.get(key_id_str) {
+        Some(k) if matches!(k.key_type, KeyType::Aes256) => k,
+        _ => return sgx_status_t::SGX_ERROR_INVALID_PARAMETER,
+    };
+    let mut key = crypto::Zeroizing::new([0u8; 32]);
+    key.copy_from_slice(&key_entry.private_key);
+    drop(state);
+
+    let mut nonce_arr = [0u8; 12];
+    nonce_arr.copy_from_slice(&nonce_slice);
+    let mut tag_arr = [0u8; 16];
+    tag_arr.copy_from_slice(&tag_slice);
+
+    let plaintext = match crypto::AesGcm::decrypt(&key, &nonce_arr, &ciphertext_slice, &aad_slice, &tag_arr) {
+        Ok(p) => p,
+        Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
+    };
+
+    unsafe {
+        std::ptr::copy_nonoverlapping(plaintext.as_ptr(), plaintext_out, plaintext.len());
+    }
+
+    sgx_status_t::SGX_SUCCESS
+}
+
+// =============================================================================
+// ECALL: Attestation-Bound Secure Channel (UKEY2-Style Handshake)
+// =============================================================================
+
+/// Ephemeral P-256 key pair generated by [`ecall_secure_handshake_init`],
+/// kept around until [`ecall_secure_handshake_finish`] consumes it.
+struct HandshakeState {
+    private_key: crypto::Zeroizing<[u8; 32]>,
+    public_key: [u8; 65],
+}
+
+/// Computes the big-endian 96-bit counter IV used by
+/// [`ecall_secure_session_encrypt`]/[`ecall_secure_session_decrypt`]: the
+/// first 4 bytes are zero, the last 8 are the counter. A monotonically
+/// increasing counter can never repeat within a session's lifetime, so the
+/// key/IV pair is never reused the way a randomly drawn IV risks under
+/// heavy reuse of the same session key.
+fn counter_iv(counter: u64) -> [u8; 12] {
+    let mut iv = [0u8; 12];
+    iv[4..12].copy_from_slice(&counter.to_be_bytes());
+    iv
+}
+
+/// Derives this channel's two directional AES-256-GCM keys and a 6-byte
+/// human-verifiable auth string from the ECDH shared secret and the full
+/// handshake transcript (both ephemeral public keys plus this enclave's
+/// id). The two ends of a handshake run this with `our_public_key`/
+/// `peer_public_key` swapped relative to each other, so the public keys
+/// are first canonicalized into a fixed `(lower, higher)` order (by byte
+/// value) before hashing, giving both sides the same transcript; direction
+/// is then resolved by comparing `our_public_key` against that order.
+fn derive_secure_channel_keys(
+    shared_secret: &[u8; 32],
+    our_public_key: &[u8; 65],
+    peer_public_key: &[u8],
+    enclave_id: &[u8; 32],
+) -> EnclaveResult<([u8; 32], [u8; 32], [u8; 6])> {
+    let we_are_lower = our_public_key.as_slice() <= peer_public_key;
+    let (lower, higher) = if we_are_lower {
+        (our_public_key.as_slice(), peer_public_key)
+    } else {
+        (peer_public_key, our_public_key.as_slice())
+    };
+
+    let mut transcript = Vec::with_capacity(lower.len() + higher.len() + 32);
+    transcript.extend_from_slice(lower);
+    transcript.extend_from_slice(higher);
+    transcript.extend_from_slice(enclave_id);
+
+    let key_lower_to_higher = crypto::hkdf_sha256(
+        shared_secret, &transcript, b"sgx-secure-channel-lower-to-higher", 32,
+    )?;
+    let key_higher_to_lower = crypto::hkdf_sha256(
+        shared_secret, &transcript, b"sgx-secure-channel-higher-to-lower", 32,
+    )?;
+    let auth_bytes = crypto::hkdf_sha256(
+        shared_secret, &transcript, b"sgx-secure-channel-auth-string", 6,
+    )?;
+
+    let mut send_key = [0u8; 32];
+    let mut recv_key = [0u8; 32];
+    if we_are_lower {
+        send_key.copy_from_slice(&key_lower_to_higher);
+        recv_key.copy_from_slice(&key_higher_to_lower);
+    } else {
+        send_key.copy_from_slice(&key_higher_to_lower);
+        recv_key.copy_from_slice(&key_lower_to_higher);
+    }
+    let mut auth_string = [0u8; 6];
+    auth_string.copy_from_slice(&auth_bytes);
+
+    Ok((send_key, recv_key, auth_string))
+}
+
+/// Begin a UKEY2-style attestation-bound handshake: generates an ephemeral
+/// P-256 key pair, binds its SHA-256 into `report_out`'s report data via
+/// [`attestation::generate_report`] (the same primitive [`ecall_generate_report`]
+/// uses), and stashes the ephemeral private key under `session_id` until
+/// [`ecall_secure_handshake_finish`] consumes it. The host is expected to
+/// turn `report_out` into a DCAP quote (as `sgx_bridge_generate_attestation`
+/// does) and publish it alongside the public key, so a remote peer can
+/// verify the channel is bound to this exact enclave's MRENCLAVE before
+/// trusting it - unlike [`ecall_session_init`]'s local, same-platform
+/// report, which the peer enclave is expected to verify directly.
+#[no_mangle]
+pub extern "C" fn ecall_secure_handshake_init(
+    session_id: *const u8,
+    session_id_len: usize,
+    target_info: *const sgx_target_info_t,
+    public_key_out: *mut u8,
+    public_key_len: usize,
+    report_out: *mut sgx_report_t,
+) -> sgx_status_t {
+    if target_info.is_null() {
+        return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+    }
+    if let Err(e) = marshal::check_in_ptr(target_info) {
+        return e;
+    }
+    if let Err(e) = marshal::check_out_ptr(report_out) {
+        return e;
+    }
+    if let Err(e) = marshal::check_out_buf(public_key_out, public_key_len, 65) {
+        return e;
+    }
+
+    let session_id_bytes = match marshal::copy_in_required(session_id, session_id_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let session_id_str = match std::str::from_utf8(&session_id_bytes) {
+        Ok(s) => String::from(s),
+        Err(_) => return sgx_status_t::SGX_ERROR_INVALID_PARAMETER,
+    };
+
+    let ephemeral = match crypto::EcdsaKeyPair::generate() {
+        Ok(k) => k,
+        Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
+    };
+    let public_key_vec = ephemeral.public_key_bytes();
+    let mut public_key_bytes = [0u8; 65];
+    public_key_bytes.copy_from_slice(&public_key_vec);
+
+    let report_data = match crypto::sha256(&public_key_bytes) {
+        Ok(h) => h,
+        Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
+    };
+
+    let ti = unsafe { *target_info };
+    let report = match attestation::generate_report(Some(&ti), &report_data) {
+        Ok(r) => r,
+        Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
+    };
+
+    let mut state = match ENCLAVE_STATE.lock() {
+        Ok(s) => s,
+        Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
+    };
+    let mut priv_bytes = crypto::Zeroizing::new([0u8; 32]);
+    priv_bytes.copy_from_slice(&ephemeral.private_key_bytes());
+    state.handshake_sessions.insert(session_id_str, HandshakeState {
+        private_key: priv_bytes,
+        public_key: public_key_bytes,
+    });
+
+    unsafe {
+        std::ptr::copy_nonoverlapping(public_key_bytes.as_ptr(), public_key_out, 65);
+        *report_out = report;
+    }
+
+    sgx_status_t::SGX_SUCCESS
+}
+
+/// Complete a UKEY2-style handshake: combine the ephemeral key pair stashed
+/// by [`ecall_secure_handshake_init`] with the peer's ephemeral public key
+/// via ECDH, derive directional AES-256-GCM session keys and a 6-byte
+/// human-verifiable auth string (see [`derive_secure_channel_keys`]), and
+/// store the result under `session_id` for
+/// [`ecall_secure_session_encrypt`]/[`ecall_secure_session_decrypt`].
+///
+/// Unlike [`ecall_session_complete`], this does not itself verify an
+/// attestation report for the peer - the peer's quote is meant to be
+/// checked out-of-band by whoever is establishing the channel (e.g. via
+/// `sgx_bridge_verify_quote`), and `auth_string_out` gives both ends a
+/// short value they can additionally compare (e.g. read aloud) to catch a
+/// man-in-the-middle that swapped in its own ephemeral key.
+#[no_mangle]
+pub extern "C" fn ecall_secure_handshake_finish(
+    session_id: *const u8,
+    session_id_len: usize,
+    peer_public_key: *const u8,
+    peer_public_key_len: usize,
+    auth_string_out: *mut u8,
+    auth_string_len: usize,
+) -> sgx_status_t {
+    if let Err(e) = marshal::check_out_buf(auth_string_out, auth_string_len, 6) {
+        return e;
+    }
+
+    let session_id_bytes = match marshal::copy_in_required(session_id, session_id_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let session_id_str = match std::str::from_utf8(&session_id_bytes) {
+        Ok(s) => String::from(s),
+        Err(_) => return sgx_status_t::SGX_ERROR_INVALID_PARAMETER,
+    };
+
+    let peer_pub_slice = match marshal::copy_in_required(peer_public_key, peer_public_key_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    if peer_pub_slice.len() != 65 || peer_pub_slice[0] != 0x04 {
+        return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+    }
+
+    let mut state = match ENCLAVE_STATE.lock() {
+        Ok(s) => s,
+        Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
+    };
+
+    let handshake = match state.handshake_sessions.remove(&session_id_str) {
+        Some(h) => h,
+        None => return sgx_status_t::SGX_ERROR_INVALID_PARAMETER,
+    };
+
+    let mut ephemeral = crypto::EcdsaKeyPair {
+        private_key: sgx_ec256_private_t::default(),
+        public_key: sgx_ec256_public_t::default(),
+    };
+    ephemeral.private_key.r.copy_from_slice(&handshake.private_key);
+
+    let shared_secret = match ephemeral.ecdh(&peer_pub_slice) {
+        Ok(s) => s,
+        Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
+    };
+
+    let (send_key, recv_key, auth_string) = match derive_secure_channel_keys(
+        &shared_secret, &handshake.public_key, &peer_pub_slice, &state.enclave_id,
+    ) {
+        Ok(v) => v,
+        Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
+    };
+
+    state.secure_sessions.insert(session_id_str, SessionKeys {
+        send_key,
+        recv_key,
+        send_counter: 0,
+        recv_counter: 0,
+    });
+
+    unsafe {
+        std::ptr::copy_nonoverlapping(auth_string.as_ptr(), auth_string_out, 6);
+    }
+
+    sgx_status_t::SGX_SUCCESS
+}
+
+/// Encrypt data under a channel established by [`ecall_secure_handshake_finish`].
+/// The IV is this direction's message counter (see [`counter_iv`]), not a
+/// random value, so `nonce_out` must be delivered to the peer alongside the
+/// ciphertext for [`ecall_secure_session_decrypt`] to check against its own
+/// expected counter.
+#[no_mangle]
+pub extern "C" fn ecall_secure_session_encrypt(
+    session_id: *const u8,
+    session_id_len: usize,
+    plaintext: *const u8,
+    plaintext_len: usize,
+    aad: *const u8,
+    aad_len: usize,
+    ciphertext_out: *mut u8,
+    ciphertext_len: usize,
+    nonce_out: *mut u8,
+    nonce_len: usize,
+    tag_out: *mut u8,
+    tag_len: usize,
+) -> sgx_status_t {
+    if let Err(e) = marshal::check_out_buf(ciphertext_out, ciphertext_len, plaintext_len) {
+        return e;
+    }
+    if let Err(e) = marshal::check_out_buf(nonce_out, nonce_len, 12) {
+        return e;
+    }
+    if let Err(e) = marshal::check_out_buf(tag_out, tag_len, 16) {
+        return e;
+    }
+
+    let session_id_bytes = match marshal::copy_in_required(session_id, session_id_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let session_id_str = match std::str::from_utf8(&session_id_bytes) {
+        Ok(s) => s,
+        Err(_) => return sgx_status_t::SGX_ERROR_INVALID_PARAMETER,
+    };
+    let plaintext_slice = match marshal::copy_in_required(plaintext, plaintext_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let aad_slice = match marshal::copy_in(aad, aad_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    let mut state = match ENCLAVE_STATE.lock() {
+        Ok(s) => s,
+        Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
+    };
+    let session = match state.secure_sessions.get_mut(session_id_str) {
+        Some(s) => s,
+        None => return sgx_status_t::SGX_ERROR_INVALID_PARAMETER,
+    };
+
+    let nonce = counter_iv(session.send_counter);
+    let key = session.send_key;
+
+    let (ciphertext, tag) = match crypto::AesGcm::encrypt(&key, &nonce, &plaintext_slice, &aad_slice) {
+        Ok(r) => r,
+        Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
+    };
+    session.send_counter += 1;
+
+    unsafe {
+        std::ptr::copy_nonoverlapping(ciphertext.as_ptr(), ciphertext_out, ciphertext.len());
+        std::ptr::copy_nonoverlapping(nonce.as_ptr(), nonce_out, 12);
+        std::ptr::copy_nonoverlapping(tag.as_ptr(), tag_out, 16);
+    }
+
+    sgx_status_t::SGX_SUCCESS
+}
+
+/// Decrypt data under a channel established by [`ecall_secure_handshake_finish`].
+/// `nonce` must equal this direction's expected counter value (see
+/// [`counter_iv`]) or the call is rejected before decryption is even
+/// attempted - this is what makes the channel replay-resistant: a captured
+/// `(ciphertext, nonce, tag)` replayed after the real message has already
+/// advanced `recv_counter` no longer matches and is refused.
+#[no_mangle]
+pub extern "C" fn ecall_secure_session_decrypt(
+    session_id: *const u8,
+    session_id_len: usize,
+    ciphertext: *const u8,
+    ciphertext_len: usize,
+    aad: *const u8,
+    aad_len: usize,
+    nonce: *const u8,
+    nonce_len: usize,
+    tag: *const u8,
+    tag_len: usize,
+    plaintext_out: *mut u8,
+    plaintext_buf_len: usize,
+) -> sgx_status_t {
+    if nonce_len != 12 || tag_len != 16 {
+        return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+    }
+    if let Err(e) = marshal::check_out_buf(plaintext_out, plaintext_buf_len, ciphertext_len) {
+        return e;
+    }
+
+    let session_id_bytes = match marshal::copy_in_required(session_id, session_id_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let session_id_str = match std::str::from_utf8(&session_id_bytes) {
+        Ok(s) => s,
+        Err(_) => return sgx_status_t::SGX_ERROR_INVALID_PARAMETER,
+    };
+    let ciphertext_slice = match marshal::copy_in_required(ciphertext, ciphertext_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let aad_slice = match marshal::copy_in(aad, aad_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let nonce_slice = match marshal::copy_in_required(nonce, nonce_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let tag_slice = match marshal::copy_in_required(tag, tag_len) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    let mut state = match ENCLAVE_STATE.lock() {
+        Ok(s) => s,
+        Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
+    };
+    let session = match state.secure_sessions.get_mut(session_id_str) {
+        Some(s) => s,
+        None => return sgx_status_t::SGX_ERROR_INVALID_PARAMETER,
+    };
+
+    let expected_nonce = counter_iv(session.recv_counter);
+    if nonce_slice[..] != expected_nonce[..] {
+        return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+    }
+
+    let mut nonce_arr = [0u8; 12];
+    nonce_arr.copy_from_slice(&nonce_slice);
+    let mut tag_arr = [0u8; 16];
+    tag_arr.copy_from_slice(&tag_slice);
+    let key = session.recv_key;
+
+    let plaintext = match crypto::AesGcm::decrypt(&key, &nonce_arr, &ciphertext_slice, &aad_slice, &tag_arr) {
+        Ok(p) => p,
+        Err(_) => return sgx_status_t::SGX_ERROR_UNEXPECTED,
+    };
+    session.recv_counter += 1;
+
+    unsafe {
+        std::ptr::copy_nonoverlapping(plaintext.as_ptr(), plaintext_out, plaintext.len());
+    }
+
+    sgx_status_t::SGX_SUCCESS
+}
+
+// =============================================================================
+// ECALL: Get Enclave Info
+// =============================================================================
+
+/// Get enclave measurement (MRENCLAVE) and signer (MRSIGNER).
+#[no_mangle]
+pub extern "C" fn ecall_get_enclave_info(
+    mr_enclave_out: *mut u8,
+    mr_signer_out: *mut u8,
+) -> sgx_status_t {
+    if let Err(e) = marshal::check_out_buf(mr_enclave_out, 32, 32) {
+        return e;
+    }
+    if let Err(e) = marshal::check_out_buf(mr_signer_out, 32, 32) {
+        return e;
     }
 
     // Create a self-report to get MRENCLAVE and MRSIGNER
@@ -0,0 +1,111 @@
+//! TOCTOU-safe marshalling helpers for ECALL boundary parameters.
+//!
+//! Every ECALL here is a hand-written `extern "C"` entry point rather than
+//! an edger8r-generated trampoline, so nothing marshals host buffers into
+//! enclave memory on our behalf. Dereferencing the same host pointer twice
+//! - once to validate a length, again to use the bytes - lets a malicious
+//! or merely buggy host mutate the data in between (a double fetch). These
+//! helpers copy an untrusted `(ptr, len)` pair into an enclave-owned buffer
+//! in a single read, and centralize the pointer/length and output-size
+//! checks every ECALL needs before touching host memory.
+//!
+//! They also reject any pointer range that isn't entirely outside the
+//! enclave's protected memory, via `sgx_trts`'s wrapper around the
+//! trusted runtime's `sgx_is_outside_enclave` (the same check edger8r
+//! generates for every ECALL parameter in a normal SDK project, modeled
+//! here on Graphene's `sgx_is_completely_outside_enclave`). A host cannot
+//! legitimately pass a pointer into the enclave's own address space as an
+//! ECALL buffer argument, so one that does is treated as an attempt to
+//! steer the enclave into reading or clobbering its own protected pages.
+
+use std::prelude::v1::*;
+use std::vec::Vec;
+
+use sgx_trts::trts::rsgx_raw_is_outside_enclave;
+use sgx_types::sgx_status_t;
+
+/// Rejects a `(ptr, len)` range that overflows the address space or is not
+/// entirely outside the enclave's protected memory.
+fn check_outside_enclave(ptr: *const u8, len: usize) -> Result<(), sgx_status_t> {
+    if (ptr as usize).checked_add(len).is_none() {
+        return Err(sgx_status_t::SGX_ERROR_INVALID_PARAMETER);
+    }
+    if !rsgx_raw_is_outside_enclave(ptr, len) {
+        return Err(sgx_status_t::SGX_ERROR_INVALID_PARAMETER);
+    }
+    Ok(())
+}
+
+/// Copies an untrusted `(ptr, len)` buffer into an enclave-owned `Vec<u8>`
+/// in one read. A null pointer or zero length is treated as "no data" and
+/// yields an empty vec, matching how ECALLs already handle optional buffers
+/// like AAD. Rejects a pair whose `ptr + len` would wrap the address space.
+pub fn copy_in(ptr: *const u8, len: usize) -> Result<Vec<u8>, sgx_status_t> {
+    if ptr.is_null() || len == 0 {
+        return Ok(Vec::new());
+    }
+    check_outside_enclave(ptr, len)?;
+    // Single fetch: nothing below this line reads `ptr` again.
+    let slice = unsafe { std::slice::from_raw_parts(ptr, len) };
+    Ok(slice.to_vec())
+}
+
+/// Like [`copy_in`], but rejects a null pointer or zero length outright
+/// instead of treating it as "no data" — for parameters that are always
+/// required (a key, a ciphertext, a signature).
+pub fn copy_in_required(ptr: *const u8, len: usize) -> Result<Vec<u8>, sgx_status_t> {
+    if ptr.is_null() || len == 0 {
+        return Err(sgx_status_t::SGX_ERROR_INVALID_PARAMETER);
+    }
+    copy_in(ptr, len)
+}
+
+/// Checks that an output buffer pointer and its claimed length are usable
+/// and large enough to hold `required` bytes, so the enclave never writes
+/// past what the host actually allocated for it.
+pub fn check_out_buf(ptr: *mut u8, buf_len: usize, required: usize) -> Result<(), sgx_status_t> {
+    if ptr.is_null() {
+        return Err(sgx_status_t::SGX_ERROR_INVALID_PARAMETER);
+    }
+    check_outside_enclave(ptr as *const u8, buf_len)?;
+    if buf_len < required {
+        return Err(sgx_status_t::SGX_ERROR_INVALID_PARAMETER);
+    }
+    Ok(())
+}
+
+/// Like [`check_out_buf`], but for a fixed-size output parameter (a struct
+/// pointer, a `*mut u32`/`*mut i32`, ...) whose size is implied by its type
+/// rather than passed alongside as a separate length - `check_out_buf`'s
+/// `buf_len < required` check doesn't apply, but the null and
+/// outside-enclave checks do.
+pub fn check_out_ptr<T>(ptr: *mut T) -> Result<(), sgx_status_t> {
+    if ptr.is_null() {
+        return Err(sgx_status_t::SGX_ERROR_INVALID_PARAMETER);
+    }
+    check_outside_enclave(ptr as *const u8, std::mem::size_of::<T>())
+}
+
+/// Like [`check_out_ptr`], but for a fixed-size *input* parameter the
+/// enclave only reads (e.g. `*const sgx_report_t`). A null pointer is
+/// treated as "not provided" and accepted, matching how these optional
+/// fixed-size parameters are already used throughout the ECALL surface.
+pub fn check_in_ptr<T>(ptr: *const T) -> Result<(), sgx_status_t> {
+    if ptr.is_null() {
+        return Ok(());
+    }
+    check_outside_enclave(ptr as *const u8, std::mem::size_of::<T>())
+}
+
+/// Like [`check_out_ptr`], but for a fixed-size *output* parameter the
+/// caller may legitimately decline (e.g. `rollback_detected_out` when the
+/// caller doesn't care to be told which it was). A null pointer is treated
+/// as "not wanted" and accepted; a non-null one is still checked the same
+/// way [`check_out_ptr`] would, so a malicious host can't point it at the
+/// enclave's own memory.
+pub fn check_out_ptr_optional<T>(ptr: *mut T) -> Result<(), sgx_status_t> {
+    if ptr.is_null() {
+        return Ok(());
+    }
+    check_outside_enclave(ptr as *const u8, std::mem::size_of::<T>())
+}
@@ -0,0 +1,340 @@
+//! Declarative attestation verification policy.
+//!
+//! Verification today is ad-hoc: callers get raw measurements out of
+//! [`crate::attestation::get_enclave_measurements`] or a verified [`Quote`]
+//! and have to compare them by hand. [`VerificationPolicy`] encodes the
+//! acceptance rules an operator actually wants to express - which
+//! MRENCLAVE/MRSIGNER identities are trusted, the minimum SVN floor for
+//! each signer, whether debug enclaves are tolerated, the minimum PCE/QE
+//! SVN, and which TCB/quote statuses are acceptable - as data, so a
+//! deployment can pin expected enclave identities via a config file
+//! instead of recompiling.
+//!
+//! The policy is expressed in a small subset of TOML: top-level
+//! `bool`/integer/string-array values plus `[[allowed_signers]]` array-of-
+//! tables entries. The enclave crate avoids pulling in external parsing
+//! crates (see the hand-rolled DER reader in [`crate::dcap`]), so
+//! [`VerificationPolicy::from_toml`] parses that subset itself rather than
+//! depending on `serde`/`toml`.
+
+use std::prelude::v1::*;
+use std::vec::Vec;
+
+use crate::attestation::Quote;
+use crate::types::{EnclaveError, EnclaveResult};
+
+/// Per-signer acceptance rule: a trusted MRSIGNER paired with the lowest
+/// ISV SVN and the ISV product ID it is allowed to vouch for.
+#[derive(Debug, Clone)]
+pub struct SignerPolicy {
+    /// Hex-encoded MRSIGNER (32 bytes).
+    pub mr_signer: String,
+    /// ISV product ID that must match exactly.
+    pub isv_prod_id: u16,
+    /// Lowest ISV SVN accepted for this signer.
+    pub min_isv_svn: u16,
+}
+
+/// Declarative acceptance rules for attestation verification.
+#[derive(Debug, Clone, Default)]
+pub struct VerificationPolicy {
+    /// Hex-encoded MRENCLAVE values that are accepted outright. Empty
+    /// means "no per-enclave allow-list" (fall through to `allowed_signers`).
+    pub allowed_mr_enclave: Vec<String>,
+    /// Trusted signers and their SVN/product-id floor.
+    pub allowed_signers: Vec<SignerPolicy>,
+    /// Whether enclaves built with `SGX_FLAGS_DEBUG` set are accepted.
+    pub allow_debug: bool,
+    /// Lowest acceptable PCE SVN.
+    pub min_pce_svn: u16,
+    /// Lowest acceptable QE SVN.
+    pub min_qe_svn: u16,
+    /// TCB/quote status strings that are tolerated (e.g. `"OK"`,
+    /// `"SW_HARDENING_NEEDED"`). Empty means only an exact `"OK"` is
+    /// accepted.
+    pub allowed_statuses: Vec<String>,
+}
+
+impl VerificationPolicy {
+    /// Parse a policy from its TOML representation.
+    pub fn from_toml(text: &str) -> EnclaveResult<Self> {
+        parse_toml(text)
+    }
+
+    /// Evaluate `quote` and its associated verification `status` against
+    /// this policy, rejecting with a descriptive [`EnclaveError`] naming
+    /// the first failed constraint.
+    pub fn evaluate(&self, quote: &Quote, status: &str) -> EnclaveResult<()> {
+        let is_debug = (quote.report_body.attributes.flags & crate::attestation::SGX_FLAGS_DEBUG) != 0;
+        if is_debug && !self.allow_debug {
+            return Err(EnclaveError::AttestationFailed(
+                "debug enclave rejected (policy requires allow_debug = true)".into(),
+            ));
+        }
+
+        if !self.allowed_statuses.is_empty() && !self.allowed_statuses.iter().any(|s| s == status) {
+            return Err(EnclaveError::AttestationFailed(format!(
+                "quote/TCB status '{}' is not in the policy's allowed-status list",
+                status
+            )));
+        }
+        if self.allowed_statuses.is_empty() && status != "OK" {
+            return Err(EnclaveError::AttestationFailed(format!(
+                "quote/TCB status '{}' is not accepted (policy has no allowed-status list, only 'OK' is implied)",
+                status
+            )));
+        }
+
+        if quote.pce_svn < self.min_pce_svn {
+            return Err(EnclaveError::AttestationFailed(format!(
+                "PCE SVN {} is below the policy floor {}",
+                quote.pce_svn, self.min_pce_svn
+            )));
+        }
+        if quote.qe_svn < self.min_qe_svn {
+            return Err(EnclaveError::AttestationFailed(format!(
+                "QE SVN {} is below the policy floor {}",
+                quote.qe_svn, self.min_qe_svn
+            )));
+        }
+
+        let mr_enclave_hex = hex_encode(&quote.report_body.mr_enclave);
+        if self.allowed_mr_enclave.iter().any(|m| m.eq_ignore_ascii_case(&mr_enclave_hex)) {
+            return Ok(());
+        }
+
+        if self.allowed_signers.is_empty() {
+            return Err(EnclaveError::AttestationFailed(format!(
+                "MRENCLAVE {} is not in the policy's allowed-enclave list",
+                mr_enclave_hex
+            )));
+        }
+
+        let mr_signer_hex = hex_encode(&quote.report_body.mr_signer);
+        let signer = self
+            .allowed_signers
+            .iter()
+            .find(|s| s.mr_signer.eq_ignore_ascii_case(&mr_signer_hex))
+            .ok_or_else(|| {
+                EnclaveError::AttestationFailed(format!(
+                    "MRSIGNER {} is not in the policy's allowed-signer list",
+                    mr_signer_hex
+                ))
+            })?;
+
+        if quote.report_body.isv_prod_id != signer.isv_prod_id {
+            return Err(EnclaveError::AttestationFailed(format!(
+                "ISV product id {} does not match the policy's product id {} for signer {}",
+                quote.report_body.isv_prod_id, signer.isv_prod_id, mr_signer_hex
+            )));
+        }
+        if quote.report_body.isv_svn < signer.min_isv_svn {
+            return Err(EnclaveError::AttestationFailed(format!(
+                "ISV SVN {} is below the policy floor {} for signer {}",
+                quote.report_body.isv_svn, signer.min_isv_svn, mr_signer_hex
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+pub(crate) fn hex_encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() * 2);
+    for b in bytes {
+        out.push_str(&format!("{:02x}", b));
+    }
+    out
+}
+
+/// Parse the restricted TOML subset the policy file uses: top-level
+/// `key = value` pairs (bool, integer, string, or string array) and
+/// `[[allowed_signers]]` array-of-table sections. Comments (`#...`) and
+/// blank lines are ignored.
+fn parse_toml(text: &str) -> EnclaveResult<VerificationPolicy> {
+    let mut policy = VerificationPolicy::default();
+    let mut current_signer: Option<SignerPolicy> = None;
+
+    for raw_line in text.lines() {
+        let line = strip_comment(raw_line).trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if line == "[[allowed_signers]]" {
+            if let Some(signer) = current_signer.take() {
+                policy.allowed_signers.push(signer);
+            }
+            current_signer = Some(SignerPolicy { mr_signer: String::new(), isv_prod_id: 0, min_isv_svn: 0 });
+            continue;
+        }
+        if line.starts_with('[') {
+            return Err(EnclaveError::Internal(format!("unsupported TOML section: {}", line)));
+        }
+
+        let (key, value) = split_key_value(line)?;
+
+        if let Some(signer) = current_signer.as_mut() {
+            match key {
+                "mr_signer" => signer.mr_signer = parse_string(value)?,
+                "isv_prod_id" => signer.isv_prod_id = parse_int(value)? as u16,
+                "min_isv_svn" => signer.min_isv_svn = parse_int(value)? as u16,
+                other => return Err(EnclaveError::Internal(format!("unknown key in [[allowed_signers]]: {}", other))),
+            }
+            continue;
+        }
+
+        match key {
+            "allowed_mr_enclave" => policy.allowed_mr_enclave = parse_string_array(value)?,
+            "allow_debug" => policy.allow_debug = parse_bool(value)?,
+            "min_pce_svn" => policy.min_pce_svn = parse_int(value)? as u16,
+            "min_qe_svn" => policy.min_qe_svn = parse_int(value)? as u16,
+            "allowed_statuses" => policy.allowed_statuses = parse_string_array(value)?,
+            other => return Err(EnclaveError::Internal(format!("unknown policy key: {}", other))),
+        }
+    }
+
+    if let Some(signer) = current_signer.take() {
+        policy.allowed_signers.push(signer);
+    }
+
+    Ok(policy)
+}
+
+fn strip_comment(line: &str) -> &str {
+    match line.find('#') {
+        Some(idx) => &line[..idx],
+        None => line,
+    }
+}
+
+fn split_key_value(line: &str) -> EnclaveResult<(&str, &str)> {
+    let idx = line
+        .find('=')
+        .ok_or_else(|| EnclaveError::Internal(format!("expected 'key = value', got: {}", line)))?;
+    Ok((line[..idx].trim(), line[idx + 1..].trim()))
+}
+
+fn parse_bool(value: &str) -> EnclaveResult<bool> {
+    match value {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        other => Err(EnclaveError::Internal(format!("expected a boolean, got: {}", other))),
+    }
+}
+
+fn parse_int(value: &str) -> EnclaveResult<u64> {
+    value
+        .parse::<u64>()
+        .map_err(|_| EnclaveError::Internal(format!("expected an integer, got: {}", value)))
+}
+
+fn parse_string(value: &str) -> EnclaveResult<String> {
+    if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+        Ok(value[1..value.len() - 1].to_string())
+    } else {
+        Err(EnclaveError::Internal(format!("expected a quoted string, got: {}", value)))
+    }
+}
+
+fn parse_string_array(value: &str) -> EnclaveResult<Vec<String>> {
+    let inner = value
+        .strip_prefix('[')
+        .and_then(|v| v.strip_suffix(']'))
+        .ok_or_else(|| EnclaveError::Internal(format!("expected an array, got: {}", value)))?;
+    let inner = inner.trim();
+    if inner.is_empty() {
+        return Ok(Vec::new());
+    }
+    inner.split(',').map(|item| parse_string(item.trim())).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attestation::{Attributes, ReportBody};
+
+    fn quote_with(mr_enclave: [u8; 32], mr_signer: [u8; 32], isv_prod_id: u16, isv_svn: u16, debug: bool) -> Quote {
+        let mut quote = Quote {
+            version: 3,
+            sign_type: 2,
+            epid_group_id: [0; 4],
+            qe_svn: 5,
+            pce_svn: 5,
+            xeid: 0,
+            basename: [0; 32],
+            report_body: ReportBody::default(),
+            signature_len: 0,
+            signature: Vec::new(),
+        };
+        quote.report_body.mr_enclave = mr_enclave;
+        quote.report_body.mr_signer = mr_signer;
+        quote.report_body.isv_prod_id = isv_prod_id;
+        quote.report_body.isv_svn = isv_svn;
+        quote.report_body.attributes = Attributes { flags: if debug { crate::attestation::SGX_FLAGS_DEBUG } else { 0 }, xfrm: 0 };
+        quote
+    }
+
+    #[test]
+    fn test_parse_toml_roundtrip() {
+        let text = r#"
+            allowed_mr_enclave = ["aa00", "bb11"]
+            allow_debug = false
+            min_pce_svn = 3
+            min_qe_svn = 2
+            allowed_statuses = ["OK", "SW_HARDENING_NEEDED"]
+
+            [[allowed_signers]]
+            mr_signer = "cc22"
+            isv_prod_id = 7
+            min_isv_svn = 4
+        "#;
+        let policy = VerificationPolicy::from_toml(text).unwrap();
+        assert_eq!(policy.allowed_mr_enclave, vec!["aa00", "bb11"]);
+        assert!(!policy.allow_debug);
+        assert_eq!(policy.min_pce_svn, 3);
+        assert_eq!(policy.min_qe_svn, 2);
+        assert_eq!(policy.allowed_statuses, vec!["OK", "SW_HARDENING_NEEDED"]);
+        assert_eq!(policy.allowed_signers.len(), 1);
+        assert_eq!(policy.allowed_signers[0].mr_signer, "cc22");
+        assert_eq!(policy.allowed_signers[0].isv_prod_id, 7);
+        assert_eq!(policy.allowed_signers[0].min_isv_svn, 4);
+    }
+
+    #[test]
+    fn test_evaluate_rejects_debug_enclave_by_default() {
+        let policy = VerificationPolicy { allowed_mr_enclave: vec![hex_encode(&[1u8; 32])], ..Default::default() };
+        let quote = quote_with([1u8; 32], [2u8; 32], 1, 1, true);
+        let err = policy.evaluate(&quote, "OK").unwrap_err();
+        assert!(matches!(err, EnclaveError::AttestationFailed(_)));
+    }
+
+    #[test]
+    fn test_evaluate_rejects_svn_below_floor() {
+        let policy = VerificationPolicy {
+            allowed_signers: vec![SignerPolicy { mr_signer: hex_encode(&[2u8; 32]), isv_prod_id: 1, min_isv_svn: 10 }],
+            ..Default::default()
+        };
+        let quote = quote_with([1u8; 32], [2u8; 32], 1, 3, false);
+        let err = policy.evaluate(&quote, "OK").unwrap_err();
+        assert!(matches!(err, EnclaveError::AttestationFailed(_)));
+    }
+
+    #[test]
+    fn test_evaluate_accepts_matching_signer() {
+        let policy = VerificationPolicy {
+            allowed_signers: vec![SignerPolicy { mr_signer: hex_encode(&[2u8; 32]), isv_prod_id: 1, min_isv_svn: 2 }],
+            ..Default::default()
+        };
+        let quote = quote_with([1u8; 32], [2u8; 32], 1, 5, false);
+        assert!(policy.evaluate(&quote, "OK").is_ok());
+    }
+
+    #[test]
+    fn test_evaluate_rejects_status_outside_allow_list() {
+        let policy = VerificationPolicy { allowed_mr_enclave: vec![hex_encode(&[1u8; 32])], ..Default::default() };
+        let quote = quote_with([1u8; 32], [2u8; 32], 1, 1, false);
+        let err = policy.evaluate(&quote, "GROUP_OUT_OF_DATE").unwrap_err();
+        assert!(matches!(err, EnclaveError::AttestationFailed(_)));
+    }
+}
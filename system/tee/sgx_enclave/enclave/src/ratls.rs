@@ -0,0 +1,373 @@
+//! RA-TLS: bind remote attestation to a TLS session.
+//!
+//! [`ChannelBinding`](crate::attestation::ChannelBinding) exists but is
+//! never wired to anything - its `timestamp` is hard-coded to zero and
+//! nothing ties a TLS session's key material to an attested enclave
+//! identity. This module closes that gap: the server generates an
+//! ephemeral P-256 key pair for the TLS session, binds it to the
+//! enclave's identity by hashing the public key into the attestation
+//! report's `report_data`, and carries the resulting evidence inside a
+//! self-signed X.509 certificate's custom extension. A peer that verifies
+//! the certificate (chain-of-trust-free, since the "chain of trust" here
+//! is the attestation evidence itself) learns both "this certificate's
+//! key belongs to this TLS session" and "this TLS session is held by this
+//! specific, policy-approved enclave" - mutually-attested TLS.
+//!
+//! The embedded evidence is whatever [`AttestationEvidence`] the caller
+//! attached to the report (a local self-report for same-platform
+//! channels, or a full DCAP quote + PCK chain for cross-platform ones);
+//! [`verify_ratls_cert`] runs it through [`crate::dcap::verify_dcap_quote`]
+//! when a DCAP quote is present, then checks the binding.
+
+use std::prelude::v1::*;
+use std::vec::Vec;
+
+use crate::attestation::{generate_self_report, AttestationEvidence, Quote, ReportBody};
+use crate::crypto::{self, EcdsaKeyPair};
+use crate::dcap;
+use crate::policy::VerificationPolicy;
+use crate::types::{EnclaveError, EnclaveResult};
+
+/// Private enterprise OID used to carry RA-TLS evidence in a certificate
+/// extension: `1.2.840.113741.1337.6` (Intel's enterprise arc, under the
+/// same numbering the `linux-sgx-ra-tls` reference implementation uses
+/// for its own SGX-quote extension).
+const RATLS_EVIDENCE_OID: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf8, 0x4d, 0x8a, 0x39, 0x06];
+
+const OID_EC_PUBLIC_KEY: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01];
+const OID_PRIME256V1: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07];
+const OID_ECDSA_WITH_SHA256: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x02];
+const OID_COMMON_NAME: &[u8] = &[0x55, 0x04, 0x03];
+
+/// Generate a self-signed RA-TLS certificate binding `key`'s public key to
+/// this enclave's attested identity.
+///
+/// Before signing anything, the enclave's own measurements are checked
+/// against `policy` - a cert is only worth issuing if it would also pass
+/// verification, so a misconfigured policy fails loudly at generation
+/// time instead of producing a certificate every peer will reject.
+///
+/// Returns `(cert_der, key_der)`: the self-signed certificate and `key`'s
+/// private key in SEC1 `ECPrivateKey` DER encoding (RFC 5915), ready to
+/// hand to a TLS stack.
+pub fn generate_ratls_cert(key: &EcdsaKeyPair, policy: &VerificationPolicy) -> EnclaveResult<(Vec<u8>, Vec<u8>)> {
+    let public_key = key.public_key_bytes();
+    let key_hash = crypto::sha256(&public_key)?;
+
+    let mut report_data = [0u8; 64];
+    report_data[..32].copy_from_slice(&key_hash);
+
+    let report = generate_self_report(&report_data)?;
+    let evidence = AttestationEvidence::from_report(&report);
+
+    policy.evaluate(&evidence.quote, "OK")?;
+
+    let evidence_bytes = serialize_evidence(&evidence);
+    let tbs = build_tbs_certificate(&public_key, &evidence_bytes);
+    let signature = key.sign_der(&tbs)?;
+
+    let cert = der_sequence([tbs, alg_id(OID_ECDSA_WITH_SHA256), der_bit_string(&signature)].concat());
+
+    Ok((cert, build_ec_private_key_der(key)))
+}
+
+/// Verify an RA-TLS certificate: check its self-signature, extract the
+/// embedded attestation evidence, run it through DCAP verification (when a
+/// quote signature section is present) or accept the quote's own report
+/// body as-is for a local self-attestation, confirm the result satisfies
+/// `policy`, and confirm the certificate's public key is the one the
+/// attested `report_data` committed to.
+///
+/// Returns the verified report body on success.
+pub fn verify_ratls_cert(cert_der: &[u8], policy: &VerificationPolicy, now: u64) -> EnclaveResult<ReportBody> {
+    let (cert, trailing) = dcap::parse_certificate(cert_der)?;
+    if !trailing.is_empty() {
+        return Err(EnclaveError::AttestationFailed("trailing data after RA-TLS certificate".to_string()));
+    }
+
+    if !EcdsaKeyPair::verify_with_public_key(&cert.public_key, cert.tbs_certificate, &cert.signature)? {
+        return Err(EnclaveError::AttestationFailed("RA-TLS certificate self-signature is invalid".to_string()));
+    }
+
+    let extensions = cert
+        .extensions
+        .ok_or_else(|| EnclaveError::AttestationFailed("RA-TLS certificate has no extensions".to_string()))?;
+    let evidence_bytes = find_extension(extensions, RATLS_EVIDENCE_OID)?;
+    let evidence = deserialize_evidence(&evidence_bytes)?;
+
+    let report_body = if evidence.quote_signature_data.is_some() {
+        let verified = dcap::verify_dcap_quote(&evidence, now)?;
+        policy_check_verified(policy, &evidence.quote, &verified)?;
+        evidence.quote.report_body.clone()
+    } else {
+        policy.evaluate(&evidence.quote, "OK")?;
+        evidence.quote.report_body.clone()
+    };
+
+    let expected_hash = crypto::sha256(&cert.public_key)?;
+    if report_body.report_data[..32] != expected_hash[..] {
+        return Err(EnclaveError::AttestationFailed(
+            "attested report_data does not commit to this certificate's public key".to_string(),
+        ));
+    }
+
+    Ok(report_body)
+}
+
+/// Re-check a DCAP-verified report against `policy`: `verify_dcap_quote`
+/// only authenticates the quote's signature chain, so the acceptance
+/// decision still runs through the same policy used at generation time.
+fn policy_check_verified(policy: &VerificationPolicy, quote: &Quote, verified: &dcap::VerifiedReport) -> EnclaveResult<()> {
+    if verified.mr_enclave != quote.report_body.mr_enclave || verified.mr_signer != quote.report_body.mr_signer {
+        return Err(EnclaveError::AttestationFailed(
+            "verified report does not match the quote embedded in the certificate".to_string(),
+        ));
+    }
+    policy.evaluate(quote, "OK")
+}
+
+fn serialize_evidence(evidence: &AttestationEvidence) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_length_prefixed(&mut out, &evidence.quote.to_bytes());
+    write_length_prefixed(&mut out, evidence.cert_chain.as_deref().unwrap_or(&[]));
+    write_length_prefixed(&mut out, evidence.quote_signature_data.as_deref().unwrap_or(&[]));
+    out
+}
+
+fn deserialize_evidence(bytes: &[u8]) -> EnclaveResult<AttestationEvidence> {
+    let mut cursor = bytes;
+    let quote_bytes = read_length_prefixed(&mut cursor)?;
+    let cert_chain = read_length_prefixed(&mut cursor)?;
+    let quote_signature_data = read_length_prefixed(&mut cursor)?;
+
+    Ok(AttestationEvidence {
+        quote: Quote::from_bytes(&quote_bytes)?,
+        cert_chain: if cert_chain.is_empty() { None } else { Some(cert_chain) },
+        collateral: None,
+        quote_signature_data: if quote_signature_data.is_empty() { None } else { Some(quote_signature_data) },
+    })
+}
+
+fn write_length_prefixed(out: &mut Vec<u8>, data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    out.extend_from_slice(data);
+}
+
+fn read_length_prefixed(cursor: &mut &[u8]) -> EnclaveResult<Vec<u8>> {
+    let bad = || EnclaveError::AttestationFailed("truncated RA-TLS evidence encoding".to_string());
+    if cursor.len() < 4 {
+        return Err(bad());
+    }
+    let (len_bytes, rest) = cursor.split_at(4);
+    let len = u32::from_le_bytes(len_bytes.try_into().unwrap()) as usize;
+    if rest.len() < len {
+        return Err(bad());
+    }
+    let (data, tail) = rest.split_at(len);
+    *cursor = tail;
+    Ok(data.to_vec())
+}
+
+fn find_extension(extensions: &[u8], oid: &[u8]) -> EnclaveResult<Vec<u8>> {
+    let bad = |what: &str| EnclaveError::AttestationFailed(format!("malformed extensions: {}", what));
+    let mut cursor = extensions;
+    while !cursor.is_empty() {
+        let (tag, content, after) = dcap::read_tlv(cursor)?;
+        cursor = after;
+        if tag != 0x30 {
+            return Err(bad("expected Extension SEQUENCE"));
+        }
+
+        // Extension ::= SEQUENCE { extnID OID, critical BOOLEAN DEFAULT FALSE, extnValue OCTET STRING }
+        let (oid_tag, oid_bytes, rest) = dcap::read_tlv(content)?;
+        if oid_tag != 0x06 {
+            return Err(bad("expected extnID OID"));
+        }
+        let (second_tag, second_content, rest) = dcap::read_tlv(rest)?;
+        let extn_value = if second_tag == 0x01 {
+            let (value_tag, value_content, _) = dcap::read_tlv(rest)?;
+            if value_tag != 0x04 {
+                return Err(bad("expected extnValue OCTET STRING"));
+            }
+            value_content
+        } else if second_tag == 0x04 {
+            second_content
+        } else {
+            return Err(bad("expected critical BOOLEAN or extnValue OCTET STRING"));
+        };
+
+        if oid_bytes == oid {
+            return Ok(extn_value.to_vec());
+        }
+    }
+    Err(bad("RA-TLS evidence extension not found"))
+}
+
+/// Build the `tbsCertificate` for a self-signed RA-TLS leaf: subject and
+/// issuer are the same (it is self-signed), the key is `public_key`, and
+/// the attestation evidence sits in a custom extension under
+/// [`RATLS_EVIDENCE_OID`]. Validity is set to a wide, fixed window -
+/// freshness for an ephemeral session certificate comes from the embedded
+/// quote, not the X.509 `notBefore`/`notAfter` fields.
+fn build_tbs_certificate(public_key: &[u8], evidence_bytes: &[u8]) -> Vec<u8> {
+    let version = der_context(0, true, &der_integer(&[0x02])); // v3
+    let serial = der_integer(&[0x01]);
+    let signature_alg = alg_id(OID_ECDSA_WITH_SHA256);
+    let name = build_name("RA-TLS Enclave");
+    let validity = der_sequence([der_time(b"20250101000000Z"), der_time(b"20491231235959Z")].concat());
+    let spki = der_sequence(
+        [
+            der_sequence([der_oid(OID_EC_PUBLIC_KEY), der_oid(OID_PRIME256V1)].concat()),
+            der_bit_string(public_key),
+        ]
+        .concat(),
+    );
+    let extension = der_sequence([der_oid(RATLS_EVIDENCE_OID), der_octet_string(evidence_bytes)].concat());
+    let extensions = der_context(3, true, &der_sequence(extension));
+
+    der_sequence(
+        [version, serial, signature_alg, name.clone(), validity, name, spki, extensions].concat(),
+    )
+}
+
+fn build_name(common_name: &str) -> Vec<u8> {
+    let attr = der_sequence([der_oid(OID_COMMON_NAME), der_tlv(0x0c, common_name.as_bytes())].concat());
+    der_sequence(der_tlv(0x31, &attr))
+}
+
+/// Serialize `key`'s private key as a SEC1 `ECPrivateKey` (RFC 5915), the
+/// traditional "EC PRIVATE KEY" DER encoding most TLS stacks accept
+/// directly alongside a certificate.
+fn build_ec_private_key_der(key: &EcdsaKeyPair) -> Vec<u8> {
+    let private_key = der_octet_string(&key.private_key_bytes());
+    let parameters = der_context(0, true, &der_oid(OID_PRIME256V1));
+    let public_key = der_context(1, true, &der_bit_string(&key.public_key_bytes()));
+    der_sequence([der_integer(&[0x01]), private_key, parameters, public_key].concat())
+}
+
+fn alg_id(oid: &[u8]) -> Vec<u8> {
+    der_sequence(der_oid(oid))
+}
+
+fn der_time(value: &[u8]) -> Vec<u8> {
+    der_tlv(0x18, value) // GeneralizedTime
+}
+
+fn der_tlv(tag: u8, value: &[u8]) -> Vec<u8> {
+    let mut out = vec![tag];
+    out.extend_from_slice(&der_length(value.len()));
+    out.extend_from_slice(value);
+    out
+}
+
+fn der_length(len: usize) -> Vec<u8> {
+    if len < 0x80 {
+        vec![len as u8]
+    } else {
+        let mut bytes = Vec::new();
+        let mut n = len;
+        while n > 0 {
+            bytes.insert(0, (n & 0xff) as u8);
+            n >>= 8;
+        }
+        let mut out = vec![0x80 | bytes.len() as u8];
+        out.extend_from_slice(&bytes);
+        out
+    }
+}
+
+fn der_sequence(value: Vec<u8>) -> Vec<u8> {
+    der_tlv(0x30, &value)
+}
+
+fn der_oid(bytes: &[u8]) -> Vec<u8> {
+    der_tlv(0x06, bytes)
+}
+
+fn der_octet_string(bytes: &[u8]) -> Vec<u8> {
+    der_tlv(0x04, bytes)
+}
+
+fn der_bit_string(bytes: &[u8]) -> Vec<u8> {
+    let mut value = vec![0x00]; // zero unused bits
+    value.extend_from_slice(bytes);
+    der_tlv(0x03, &value)
+}
+
+/// Encode a non-negative integer from its big-endian magnitude, inserting
+/// a leading zero byte if the high bit is set (so DER doesn't read it as
+/// negative).
+fn der_integer(magnitude: &[u8]) -> Vec<u8> {
+    if !magnitude.is_empty() && magnitude[0] & 0x80 != 0 {
+        let mut padded = vec![0x00];
+        padded.extend_from_slice(magnitude);
+        der_tlv(0x02, &padded)
+    } else {
+        der_tlv(0x02, magnitude)
+    }
+}
+
+/// Wrap `value` in a context-specific tag (`[tag_number]`), explicit
+/// (constructed) for every use in this module.
+fn der_context(tag_number: u8, _explicit: bool, value: &[u8]) -> Vec<u8> {
+    der_tlv(0xa0 | tag_number, value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A policy that allows this enclave's own current measurements -
+    /// generation enforces the policy against the local identity, so a
+    /// fully-empty (fail-closed) policy would always be rejected.
+    fn self_trusting_policy() -> VerificationPolicy {
+        let own = crate::attestation::get_enclave_measurements().unwrap();
+        VerificationPolicy {
+            allow_debug: own.is_debug,
+            allowed_mr_enclave: vec![crate::policy::hex_encode(&own.mr_enclave)],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_generate_and_verify_ratls_cert_round_trip() {
+        let key = EcdsaKeyPair::generate().unwrap();
+        let policy = self_trusting_policy();
+
+        let (cert_der, _key_der) = generate_ratls_cert(&key, &policy).unwrap();
+        let report_body = verify_ratls_cert(&cert_der, &policy, 0).unwrap();
+
+        assert_eq!(report_body.mr_enclave.len(), 32);
+    }
+
+    #[test]
+    fn test_verify_rejects_tampered_certificate() {
+        let key = EcdsaKeyPair::generate().unwrap();
+        let policy = self_trusting_policy();
+
+        let (mut cert_der, _key_der) = generate_ratls_cert(&key, &policy).unwrap();
+        let last = cert_der.len() - 1;
+        cert_der[last] ^= 0xff;
+
+        assert!(verify_ratls_cert(&cert_der, &policy, 0).is_err());
+    }
+
+    #[test]
+    fn test_der_integer_pads_high_bit() {
+        assert_eq!(der_integer(&[0x02]), vec![0x02, 0x01, 0x02]);
+        assert_eq!(der_integer(&[0x80]), vec![0x02, 0x02, 0x00, 0x80]);
+    }
+
+    #[test]
+    fn test_evidence_round_trip_without_dcap_fields() {
+        let report = generate_self_report(b"round trip").unwrap();
+        let evidence = AttestationEvidence::from_report(&report);
+
+        let bytes = serialize_evidence(&evidence);
+        let restored = deserialize_evidence(&bytes).unwrap();
+
+        assert!(restored.cert_chain.is_none());
+        assert!(restored.quote_signature_data.is_none());
+        assert_eq!(restored.quote.report_body.mr_enclave, evidence.quote.report_body.mr_enclave);
+    }
+}
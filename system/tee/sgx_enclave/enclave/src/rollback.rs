@@ -0,0 +1,142 @@
+//! Anti-rollback protection using SGX monotonic counters.
+//!
+//! Sealed data on its own only protects confidentiality and integrity - a
+//! host that keeps an old copy of a sealed blob around can still feed it
+//! back to the enclave later (a rollback/replay of secret state). SGX's
+//! Platform Service Enclave exposes a small set of monotonic counters backed
+//! by the platform's trusted non-volatile storage to defend against this:
+//! a counter only ever increases, and its current value survives even if
+//! the host restores stale files from backup.
+//!
+//! This module wraps the PSE monotonic-counter API (`sgx_tservice`) and
+//! defines [`CounterBinding`], a fixed-size record binding a sealed blob to
+//! the counter value it was sealed under, so [`crate::sealing`] callers can
+//! detect a stale copy on unseal.
+
+use std::prelude::v1::*;
+use std::vec::Vec;
+
+use sgx_types::{sgx_mc_uuid_t, sgx_status_t};
+use sgx_tservice::{
+    rsgx_close_pse_session, rsgx_create_monotonic_counter, rsgx_create_pse_session,
+    rsgx_destroy_monotonic_counter, rsgx_increment_monotonic_counter, rsgx_read_monotonic_counter,
+};
+
+use crate::types::{EnclaveError, EnclaveResult};
+
+/// Size in bytes of a serialized [`CounterBinding`]: a 16-byte counter UUID
+/// plus its 4-byte little-endian value.
+pub const BINDING_LEN: usize = 20;
+
+/// Binds a sealed blob to the monotonic counter value it was sealed under,
+/// so [`crate::sealing`] can detect a stale copy on unseal.
+#[derive(Clone, Copy)]
+pub struct CounterBinding {
+    pub uuid: [u8; 16],
+    pub value: u32,
+}
+
+impl CounterBinding {
+    pub fn to_bytes(&self) -> [u8; BINDING_LEN] {
+        let mut out = [0u8; BINDING_LEN];
+        out[..16].copy_from_slice(&self.uuid);
+        out[16..].copy_from_slice(&self.value.to_le_bytes());
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < BINDING_LEN {
+            return None;
+        }
+        let mut uuid = [0u8; 16];
+        uuid.copy_from_slice(&bytes[..16]);
+        let mut value_bytes = [0u8; 4];
+        value_bytes.copy_from_slice(&bytes[16..20]);
+        Some(Self { uuid, value: u32::from_le_bytes(value_bytes) })
+    }
+}
+
+fn uuid_to_mc(uuid: &[u8; 16]) -> sgx_mc_uuid_t {
+    let mut mc = sgx_mc_uuid_t::default();
+    mc.counter_id.copy_from_slice(&uuid[..13]);
+    mc.nonce.copy_from_slice(&uuid[13..16]);
+    mc
+}
+
+fn mc_to_uuid(mc: &sgx_mc_uuid_t) -> [u8; 16] {
+    let mut uuid = [0u8; 16];
+    uuid[..13].copy_from_slice(&mc.counter_id);
+    uuid[13..16].copy_from_slice(&mc.nonce);
+    uuid
+}
+
+fn map_err(e: sgx_status_t) -> EnclaveError {
+    EnclaveError::Internal(format!("PSE monotonic counter operation failed: {:?}", e))
+}
+
+/// Creates a new monotonic counter, initialized to 0, and returns its UUID
+/// together with the initial value.
+pub fn create_counter() -> EnclaveResult<([u8; 16], u32)> {
+    rsgx_create_pse_session().map_err(map_err)?;
+    let mut value: u32 = 0;
+    let result = rsgx_create_monotonic_counter(&mut value);
+    let _ = rsgx_close_pse_session();
+    let mc = result.map_err(map_err)?;
+    Ok((mc_to_uuid(&mc), value))
+}
+
+/// Reads the current value of a monotonic counter.
+pub fn read_counter(uuid: &[u8; 16]) -> EnclaveResult<u32> {
+    rsgx_create_pse_session().map_err(map_err)?;
+    let mc = uuid_to_mc(uuid);
+    let mut value: u32 = 0;
+    let result = rsgx_read_monotonic_counter(&mc, &mut value);
+    let _ = rsgx_close_pse_session();
+    result.map_err(map_err)?;
+    Ok(value)
+}
+
+/// Increments a monotonic counter and returns its new value.
+pub fn increment_counter(uuid: &[u8; 16]) -> EnclaveResult<u32> {
+    rsgx_create_pse_session().map_err(map_err)?;
+    let mc = uuid_to_mc(uuid);
+    let mut value: u32 = 0;
+    let result = rsgx_increment_monotonic_counter(&mc, &mut value);
+    let _ = rsgx_close_pse_session();
+    result.map_err(map_err)?;
+    Ok(value)
+}
+
+/// Destroys a monotonic counter, releasing its slot in trusted storage.
+pub fn destroy_counter(uuid: &[u8; 16]) -> EnclaveResult<()> {
+    rsgx_create_pse_session().map_err(map_err)?;
+    let mc = uuid_to_mc(uuid);
+    let result = rsgx_destroy_monotonic_counter(&mc);
+    let _ = rsgx_close_pse_session();
+    result.map_err(map_err)
+}
+
+/// Prepends a [`CounterBinding`] to `aad`, for sealing a blob bound to a
+/// monotonic counter's current value.
+pub fn bind_aad(aad: &[u8], binding: CounterBinding) -> Vec<u8> {
+    let mut out = Vec::with_capacity(BINDING_LEN + aad.len());
+    out.extend_from_slice(&binding.to_bytes());
+    out.extend_from_slice(aad);
+    out
+}
+
+/// Splits a `binding ‖ aad` buffer produced by [`bind_aad`] back into its
+/// [`CounterBinding`] and the original `aad`.
+pub fn split_aad(combined: &[u8]) -> EnclaveResult<(CounterBinding, &[u8])> {
+    let binding = CounterBinding::from_bytes(combined)
+        .ok_or_else(|| EnclaveError::UnsealError("missing rollback counter binding".to_string()))?;
+    Ok((binding, &combined[BINDING_LEN..]))
+}
+
+/// Checks a [`CounterBinding`] extracted from a sealed blob against the
+/// counter's live value. Returns `Ok(true)` if the blob is current, `Ok(false)`
+/// if it is a stale (rolled-back) copy.
+pub fn check_binding(binding: &CounterBinding) -> EnclaveResult<bool> {
+    let current = read_counter(&binding.uuid)?;
+    Ok(binding.value >= current)
+}
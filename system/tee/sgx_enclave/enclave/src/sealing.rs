@@ -8,8 +8,10 @@ use std::prelude::v1::*;
 use std::vec::Vec;
 
 use sgx_types::*;
+use sgx_tse::{rsgx_get_key, rsgx_self_report};
 use sgx_tseal::SgxSealedData;
 
+use crate::crypto::{self, AesGcm, Zeroizing};
 use crate::types::{EnclaveError, EnclaveResult, SealedDataHeader};
 
 /// Sealing policy determines which enclaves can unseal the data.
@@ -118,6 +120,51 @@ pub fn unseal_data(sealed: &[u8]) -> EnclaveResult<(Vec<u8>, Vec<u8>)> {
     Ok((plaintext, aad))
 }
 
+/// Seal data with an explicit SGX key-request policy bitmask and explicit
+/// attribute/misc masks, for callers that need finer-grained control than
+/// the MRENCLAVE/MRSIGNER choice in [`seal_data`] - e.g. combining
+/// [`SGX_KEYPOLICY_CONFIGID`], [`SGX_KEYPOLICY_ISVFAMILYID`], or
+/// [`SGX_KEYPOLICY_ISVEXTPRODID`] (KSS) with MRSIGNER so sealed data is
+/// further scoped to a configuration/family/product id, not just the
+/// signer.
+///
+/// `key_policy` is any combination of the `SGX_KEYPOLICY_*` bit flags.
+/// `attribute_mask` and `misc_mask` select which bits of the enclave's
+/// attributes/misc-select must match between sealing and unsealing; use
+/// [`DEFAULT_ATTRIBUTE_MASK`] / [`DEFAULT_MISC_MASK`] to require every
+/// attribute flag and misc-select bit to match (no `xfrm` bits are masked
+/// in).
+pub fn seal_data_with_policy(
+    plaintext: &[u8],
+    aad: &[u8],
+    key_policy: u16,
+    attribute_mask: sgx_attributes_t,
+    misc_mask: sgx_misc_select_t,
+) -> EnclaveResult<Vec<u8>> {
+    if plaintext.is_empty() {
+        return Err(EnclaveError::InvalidParameter);
+    }
+
+    let sealed_size = SgxSealedData::<[u8]>::calc_raw_sealed_data_size(
+        aad.len() as u32,
+        plaintext.len() as u32,
+    ) as usize;
+
+    let sealed_data = SgxSealedData::<[u8]>::seal_data_ex(
+        key_policy,
+        attribute_mask,
+        misc_mask,
+        aad,
+        plaintext,
+    ).map_err(|e| EnclaveError::SealError(format!("Seal failed: {:?}", e)))?;
+
+    let raw_sealed = sealed_data.into_raw_sealed_data_t();
+    let sealed_ptr = &raw_sealed as *const _ as *const u8;
+    let sealed_bytes = unsafe { std::slice::from_raw_parts(sealed_ptr, sealed_size) };
+
+    Ok(sealed_bytes.to_vec())
+}
+
 /// Calculate the size of sealed data for given plaintext and AAD sizes.
 pub fn calc_sealed_size(plaintext_len: usize, aad_len: usize) -> usize {
     SgxSealedData::<[u8]>::calc_raw_sealed_data_size(
@@ -126,31 +173,99 @@ pub fn calc_sealed_size(plaintext_len: usize, aad_len: usize) -> usize {
     ) as usize
 }
 
-/// Seal data with a custom header for versioning.
+/// Sealing policy tag stored in [`SealedDataHeader::policy`].
+const POLICY_TAG_MRENCLAVE: u8 = 0;
+/// Sealing policy tag stored in [`SealedDataHeader::policy`].
+const POLICY_TAG_MRSIGNER: u8 = 1;
+
+fn policy_to_tag(policy: SealingPolicy) -> u8 {
+    match policy {
+        SealingPolicy::MrEnclave => POLICY_TAG_MRENCLAVE,
+        SealingPolicy::MrSigner => POLICY_TAG_MRSIGNER,
+    }
+}
+
+fn policy_from_tag(tag: u8) -> EnclaveResult<SealingPolicy> {
+    match tag {
+        POLICY_TAG_MRENCLAVE => Ok(SealingPolicy::MrEnclave),
+        POLICY_TAG_MRSIGNER => Ok(SealingPolicy::MrSigner),
+        other => Err(EnclaveError::UnsealError(format!("unknown sealing policy tag: {}", other))),
+    }
+}
+
+/// Builds a [`SealedDataHeader`] stamped with the enclave's own identity
+/// (MRSIGNER/ISV product id) at seal time, truncating/zero-padding
+/// `key_context_id` to the header's fixed 16-byte field.
+fn build_header(
+    plaintext_len: u32,
+    aad_len: u32,
+    policy: SealingPolicy,
+    key_context_id: &[u8],
+) -> SealedDataHeader {
+    let report = rsgx_self_report();
+
+    let mut context_id = [0u8; 16];
+    let n = key_context_id.len().min(context_id.len());
+    context_id[..n].copy_from_slice(&key_context_id[..n]);
+
+    SealedDataHeader::new(
+        plaintext_len,
+        aad_len,
+        policy_to_tag(policy),
+        report.body.isv_prod_id,
+        report.body.mr_signer.m,
+        context_id,
+    )
+}
+
+/// Metadata recovered from a [`SealedDataHeader`] by
+/// [`unseal_data_with_header`], so callers - notably state-migration code
+/// run after an enclave upgrade - can branch on the policy and identity
+/// that originally produced a blob without re-parsing the raw header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SealedMetadata {
+    /// Format version the blob was sealed with.
+    pub version: u32,
+    /// Sealing policy that produced the blob.
+    pub policy: SealingPolicy,
+    /// ISV product id of the enclave that sealed the blob.
+    pub isv_prod_id: u16,
+    /// MRSIGNER of the enclave that sealed the blob.
+    pub mr_signer: [u8; 32],
+    /// Key-derivation context id the blob was sealed under.
+    pub key_context_id: [u8; 16],
+}
+
+/// Seal data with a custom header recording format version, sealing
+/// policy, and enclave identity, for migration bookkeeping. `key_context_id`
+/// is an arbitrary caller-chosen tag (truncated to 16 bytes) identifying
+/// which logical key/purpose this blob belongs to; it is carried in the
+/// integrity-protected header, not used to derive a key here.
 pub fn seal_data_with_header(
     plaintext: &[u8],
     aad: &[u8],
     policy: SealingPolicy,
+    key_context_id: &[u8],
 ) -> EnclaveResult<Vec<u8>> {
-    // Create header
-    let header = SealedDataHeader::new(plaintext.len() as u32, aad.len() as u32);
-    let header_bytes = unsafe {
-        std::slice::from_raw_parts(
-            &header as *const _ as *const u8,
-            std::mem::size_of::<SealedDataHeader>(),
-        )
-    };
+    let header = build_header(plaintext.len() as u32, aad.len() as u32, policy, key_context_id);
+    let header_bytes = header_to_bytes(&header);
 
     // Combine header with AAD
     let mut combined_aad = Vec::with_capacity(header_bytes.len() + aad.len());
-    combined_aad.extend_from_slice(header_bytes);
+    combined_aad.extend_from_slice(&header_bytes);
     combined_aad.extend_from_slice(aad);
 
     seal_data(plaintext, &combined_aad, policy)
 }
 
-/// Unseal data and validate header.
-pub fn unseal_data_with_header(sealed: &[u8]) -> EnclaveResult<(Vec<u8>, Vec<u8>)> {
+/// Unseal data produced by [`seal_data_with_header`], validating the
+/// header and returning its parsed metadata alongside the plaintext/AAD.
+///
+/// Rejects a header whose `version` is newer than
+/// [`SealedDataHeader::VERSION`] with [`EnclaveError::UnsupportedSealVersion`]
+/// (distinct from a malformed header) since the blob needs a newer build
+/// to unseal it correctly, not a copy that merely failed integrity checks.
+pub fn unseal_data_with_header(sealed: &[u8]) -> EnclaveResult<(Vec<u8>, Vec<u8>, SealedMetadata)> {
     let (plaintext, combined_aad) = unseal_data(sealed)?;
 
     // Validate header
@@ -162,47 +277,228 @@ pub fn unseal_data_with_header(sealed: &[u8]) -> EnclaveResult<(Vec<u8>, Vec<u8>
         &*(combined_aad.as_ptr() as *const SealedDataHeader)
     };
 
+    if header.version > SealedDataHeader::VERSION {
+        return Err(EnclaveError::UnsupportedSealVersion {
+            found: header.version,
+            max_supported: SealedDataHeader::VERSION,
+        });
+    }
+
     if !header.validate() {
         return Err(EnclaveError::UnsealError("Invalid header".to_string()));
     }
 
+    let metadata = SealedMetadata {
+        version: header.version,
+        policy: policy_from_tag(header.policy)?,
+        isv_prod_id: header.isv_prod_id,
+        mr_signer: header.mr_signer,
+        key_context_id: header.key_context_id,
+    };
+
     // Extract original AAD
     let aad = combined_aad[std::mem::size_of::<SealedDataHeader>()..].to_vec();
 
-    Ok((plaintext, aad))
+    Ok((plaintext, aad, metadata))
+}
+
+/// Unseals `sealed` and reseals its plaintext/AAD under `new_policy` with a
+/// freshly stamped header - the standard MRENCLAVE -> MRSIGNER migration an
+/// enclave performs against its own persisted state after being upgraded
+/// to a new MRENCLAVE signed by the same key. The blob's original
+/// `key_context_id` is preserved across the migration.
+pub fn reseal_data(sealed: &[u8], new_policy: SealingPolicy) -> EnclaveResult<Vec<u8>> {
+    let (plaintext, aad, metadata) = unseal_data_with_header(sealed)?;
+    seal_data_with_header(&plaintext, &aad, new_policy, &metadata.key_context_id)
 }
 
-/// Key derivation using SGX sealing key.
-/// Derives a deterministic key that is unique to this enclave.
+/// SGX key-name value selecting the sealing (report) key family for
+/// `EGETKEY`, as opposed to the provisioning or attestation key families.
+const SGX_KEYSELECT_SEAL: u16 = 0x0004;
+
+/// Key derivation using the SGX hardware sealing key.
+///
+/// Derives a deterministic key unique to this enclave (and, per `policy`,
+/// to this exact enclave build or to any enclave signed by the same key):
+/// `EGETKEY` (via [`rsgx_get_key`]) yields the 128-bit hardware sealing
+/// secret scoped by `policy`'s `SGX_KEYPOLICY_*` bit and the current
+/// report's CPUSVN/ISVSVN, with a zero `key_id` (the request-level
+/// `key_id` field, not the caller's `key_id` argument - that one scopes
+/// the *output* via HKDF `info` below, not the hardware key itself). That
+/// secret is then the IKM to HKDF-SHA256, with the caller's `key_id` as
+/// the `info` parameter, expanded to `key_len` bytes. Unlike sealing a
+/// dummy buffer and slicing the result, this never touches the sealed-
+/// data blob layout, so there is no structure to leak.
 pub fn derive_key(
     key_id: &[u8],
     key_len: usize,
     policy: SealingPolicy,
 ) -> EnclaveResult<Vec<u8>> {
-    // Seal a known value with the key_id as AAD
-    // The sealing key is derived from EGETKEY, making it deterministic
-    let dummy_data = [0u8; 32];
-    let sealed = seal_data(&dummy_data, key_id, policy)?;
-
-    // Use part of the sealed data as the derived key
-    // The MAC in the sealed data is derived from the sealing key
-    if sealed.len() < key_len {
+    let report = rsgx_self_report();
+
+    let mut key_request = sgx_key_request_t::default();
+    key_request.key_name = SGX_KEYSELECT_SEAL;
+    key_request.key_policy = match policy {
+        SealingPolicy::MrEnclave => SGX_KEYPOLICY_MRENCLAVE,
+        SealingPolicy::MrSigner => SGX_KEYPOLICY_MRSIGNER,
+    };
+    key_request.isv_svn = report.body.isv_svn;
+    key_request.cpu_svn = report.body.cpu_svn;
+    key_request.attribute_mask = DEFAULT_ATTRIBUTE_MASK;
+    key_request.misc_mask = DEFAULT_MISC_MASK;
+
+    // Wrapped so the hardware-derived sealing key is scrubbed from memory
+    // as soon as it goes out of scope, rather than lingering until this
+    // stack slot is reused.
+    let sealing_key = Zeroizing::new(
+        rsgx_get_key(&key_request)
+            .map_err(|e| EnclaveError::CryptoError(format!("EGETKEY failed: {:?}", e)))?,
+    );
+
+    let derived = crypto::hkdf_sha256(&*sealing_key, &[], key_id, key_len)?;
+    if derived.len() < key_len {
         return Err(EnclaveError::BufferTooSmall {
             required: key_len,
-            provided: sealed.len(),
+            provided: derived.len(),
         });
     }
 
-    // Extract key material from the sealed blob
-    // In production, would use proper KDF
-    Ok(sealed[..key_len].to_vec())
+    Ok(derived)
+}
+
+/// Key id used to derive the AES-256-GCM sealing key for [`seal_data_aead`].
+/// Distinct from any key id an application might pass to [`derive_key`]
+/// directly, so the two derivation paths never collide.
+const AEAD_SEAL_KEY_ID: &[u8] = b"__sealing::aead_seal_key_v1";
+
+/// Seal data with AES-256-GCM, authenticating the full `SealedDataHeader`
+/// (not just the plaintext) as GCM associated data.
+///
+/// Unlike [`seal_data`], which delegates to the SGX sealing key directly,
+/// this produces a self-describing, versioned blob: `header ‖ nonce(12) ‖
+/// ciphertext ‖ tag(16)`. Any tampering with `header` (version, timestamp,
+/// or length fields) or `aad` causes [`unseal_data_aead`] to fail
+/// authentication rather than silently accepting stale metadata.
+pub fn seal_data_aead(
+    plaintext: &[u8],
+    aad: &[u8],
+    policy: SealingPolicy,
+) -> EnclaveResult<Vec<u8>> {
+    if plaintext.is_empty() {
+        return Err(EnclaveError::InvalidParameter);
+    }
+
+    let header = build_header(plaintext.len() as u32, aad.len() as u32, policy, AEAD_SEAL_KEY_ID);
+    let header_bytes = header_to_bytes(&header);
+
+    let mut key = [0u8; 32];
+    key.copy_from_slice(&derive_key(AEAD_SEAL_KEY_ID, 32, policy)?);
+
+    let mut nonce = [0u8; 12];
+    nonce.copy_from_slice(&crypto::random_bytes(12)?);
+
+    let mut associated_data = Vec::with_capacity(header_bytes.len() + aad.len());
+    associated_data.extend_from_slice(&header_bytes);
+    associated_data.extend_from_slice(aad);
+
+    let (ciphertext, tag) = AesGcm::encrypt(&key, &nonce, plaintext, &associated_data)?;
+
+    let mut out = Vec::with_capacity(header_bytes.len() + nonce.len() + ciphertext.len() + tag.len());
+    out.extend_from_slice(&header_bytes);
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&ciphertext);
+    out.extend_from_slice(&tag);
+    Ok(out)
+}
+
+/// Unseal data produced by [`seal_data_aead`]. `aad` must match the value
+/// passed to `seal_data_aead` exactly (it is authenticated, not stored).
+pub fn unseal_data_aead(
+    sealed: &[u8],
+    aad: &[u8],
+    policy: SealingPolicy,
+) -> EnclaveResult<Vec<u8>> {
+    let header_len = std::mem::size_of::<SealedDataHeader>();
+    const NONCE_LEN: usize = 12;
+    const TAG_LEN: usize = 16;
+
+    if sealed.len() < header_len + NONCE_LEN + TAG_LEN {
+        return Err(EnclaveError::UnsealError("sealed blob too short".to_string()));
+    }
+
+    let header = header_from_bytes(&sealed[..header_len]);
+    if !header.validate() {
+        return Err(EnclaveError::UnsealError("invalid header".to_string()));
+    }
+    if header.aad_len as usize != aad.len() {
+        return Err(EnclaveError::UnsealError("aad length mismatch".to_string()));
+    }
+
+    let plaintext_len = header.plaintext_len as usize;
+    if sealed.len() != header_len + NONCE_LEN + plaintext_len + TAG_LEN {
+        return Err(EnclaveError::UnsealError("sealed blob length mismatch".to_string()));
+    }
+
+    let nonce = &sealed[header_len..header_len + NONCE_LEN];
+    let ciphertext = &sealed[header_len + NONCE_LEN..header_len + NONCE_LEN + plaintext_len];
+    let tag = &sealed[header_len + NONCE_LEN + plaintext_len..];
+
+    let mut key = [0u8; 32];
+    key.copy_from_slice(&derive_key(AEAD_SEAL_KEY_ID, 32, policy)?);
+    let mut nonce_arr = [0u8; 12];
+    nonce_arr.copy_from_slice(nonce);
+    let mut tag_arr = [0u8; 16];
+    tag_arr.copy_from_slice(tag);
+
+    let mut associated_data = Vec::with_capacity(header_len + aad.len());
+    associated_data.extend_from_slice(&sealed[..header_len]);
+    associated_data.extend_from_slice(aad);
+
+    AesGcm::decrypt(&key, &nonce_arr, ciphertext, &associated_data, &tag_arr)
+        .map_err(|_| EnclaveError::UnsealError("authentication tag mismatch".to_string()))
+}
+
+fn header_to_bytes(header: &SealedDataHeader) -> Vec<u8> {
+    let bytes = unsafe {
+        std::slice::from_raw_parts(
+            header as *const _ as *const u8,
+            std::mem::size_of::<SealedDataHeader>(),
+        )
+    };
+    bytes.to_vec()
+}
+
+fn header_from_bytes(bytes: &[u8]) -> SealedDataHeader {
+    unsafe { *(bytes.as_ptr() as *const SealedDataHeader) }
 }
 
 // SGX sealing constants
 const SGX_KEYPOLICY_MRENCLAVE: u16 = 0x0001;
+/// Bound to the enclave's signer (MRSIGNER) - combine with the KSS bits
+/// below for [`seal_data_with_policy`].
+pub const SGX_KEYPOLICY_MRSIGNER: u16 = 0x0002;
+/// Exclude ISV product id from the key derivation.
+pub const SGX_KEYPOLICY_NOISVPRODID: u16 = 0x0004;
+/// KSS: scope the key to the enclave's CONFIGID.
+pub const SGX_KEYPOLICY_CONFIGID: u16 = 0x0008;
+/// KSS: scope the key to the enclave's ISVFAMILYID.
+pub const SGX_KEYPOLICY_ISVFAMILYID: u16 = 0x0010;
+/// KSS: scope the key to the enclave's ISVEXTPRODID.
+pub const SGX_KEYPOLICY_ISVEXTPRODID: u16 = 0x0020;
 const TSEAL_DEFAULT_FLAGSMASK: u64 = 0xFFFFFFFFFFFFFFFF;
 const TSEAL_DEFAULT_MISCMASK: u32 = 0xFFFFFFFF;
 
+/// The SDK's default attribute mask for [`seal_data_with_policy`]: every
+/// attribute `flags` bit must match between sealing and unsealing; the
+/// `xfrm` mask is zero, so `xfrm` bits are not required to match.
+pub const DEFAULT_ATTRIBUTE_MASK: sgx_attributes_t = sgx_attributes_t {
+    flags: TSEAL_DEFAULT_FLAGSMASK,
+    xfrm: 0,
+};
+/// The SDK's default misc-select mask for [`seal_data_with_policy`]: every
+/// misc-select bit must match between sealing and unsealing.
+pub const DEFAULT_MISC_MASK: sgx_misc_select_t = TSEAL_DEFAULT_MISCMASK;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -224,11 +520,75 @@ mod tests {
         let plaintext = b"versioned secret";
         let aad = b"metadata";
 
-        let sealed = seal_data_with_header(plaintext, aad, SealingPolicy::MrSigner).unwrap();
-        let (unsealed_plaintext, unsealed_aad) = unseal_data_with_header(&sealed).unwrap();
+        let sealed = seal_data_with_header(plaintext, aad, SealingPolicy::MrSigner, b"key-ctx").unwrap();
+        let (unsealed_plaintext, unsealed_aad, metadata) = unseal_data_with_header(&sealed).unwrap();
 
         assert_eq!(plaintext.as_slice(), unsealed_plaintext.as_slice());
         assert_eq!(aad.as_slice(), unsealed_aad.as_slice());
+        assert_eq!(metadata.version, SealedDataHeader::VERSION);
+        assert_eq!(metadata.policy, SealingPolicy::MrSigner);
+        assert_eq!(&metadata.key_context_id[..7], b"key-ctx");
+    }
+
+    #[test]
+    fn test_reseal_data_changes_policy() {
+        let plaintext = b"persisted enclave state";
+        let aad = b"state-v1";
+
+        let sealed = seal_data_with_header(plaintext, aad, SealingPolicy::MrEnclave, b"vault").unwrap();
+        let resealed = reseal_data(&sealed, SealingPolicy::MrSigner).unwrap();
+
+        let (unsealed_plaintext, unsealed_aad, metadata) = unseal_data_with_header(&resealed).unwrap();
+        assert_eq!(plaintext.as_slice(), unsealed_plaintext.as_slice());
+        assert_eq!(aad.as_slice(), unsealed_aad.as_slice());
+        assert_eq!(metadata.policy, SealingPolicy::MrSigner);
+        assert_eq!(&metadata.key_context_id[..5], b"vault");
+    }
+
+    #[test]
+    fn test_unseal_with_header_rejects_future_version() {
+        let plaintext = b"versioned secret";
+        let aad = b"metadata";
+
+        let sealed = seal_data_with_header(plaintext, aad, SealingPolicy::MrSigner, b"ctx").unwrap();
+        let (_, combined_aad) = unseal_data(&sealed).unwrap();
+        let mut header = header_from_bytes(&combined_aad[..std::mem::size_of::<SealedDataHeader>()]);
+        header.version = SealedDataHeader::VERSION + 1;
+
+        let mut tampered_aad = header_to_bytes(&header);
+        tampered_aad.extend_from_slice(&combined_aad[std::mem::size_of::<SealedDataHeader>()..]);
+        let resealed = seal_data(plaintext, &tampered_aad, SealingPolicy::MrSigner).unwrap();
+
+        match unseal_data_with_header(&resealed) {
+            Err(EnclaveError::UnsupportedSealVersion { found, max_supported }) => {
+                assert_eq!(found, SealedDataHeader::VERSION + 1);
+                assert_eq!(max_supported, SealedDataHeader::VERSION);
+            }
+            other => panic!("expected UnsupportedSealVersion, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_seal_unseal_aead_roundtrip() {
+        let plaintext = b"secret data to seal";
+        let aad = b"additional authenticated data";
+
+        let sealed = seal_data_aead(plaintext, aad, SealingPolicy::MrSigner).unwrap();
+        let unsealed = unseal_data_aead(&sealed, aad, SealingPolicy::MrSigner).unwrap();
+
+        assert_eq!(plaintext.as_slice(), unsealed.as_slice());
+    }
+
+    #[test]
+    fn test_seal_aead_rejects_tampered_header() {
+        let plaintext = b"secret data to seal";
+        let aad = b"additional authenticated data";
+
+        let mut sealed = seal_data_aead(plaintext, aad, SealingPolicy::MrSigner).unwrap();
+        // Flip a byte in the header's timestamp field.
+        sealed[8] ^= 0xFF;
+
+        assert!(unseal_data_aead(&sealed, aad, SealingPolicy::MrSigner).is_err());
     }
 
     #[test]
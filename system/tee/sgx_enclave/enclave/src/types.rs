@@ -24,8 +24,38 @@ pub enum EnclaveError {
     BufferTooSmall { required: usize, provided: usize },
     /// Operation not supported.
     NotSupported,
+    /// Public-key recovery failed (malformed signature or recovery id).
+    RecoveryFailed(String),
+    /// Key agreement (ECDH) failed.
+    AgreementFailed(String),
+    /// A key-agreement or signature-recovery computation produced the point
+    /// at infinity, which has no valid x-coordinate to use as a secret.
+    PointAtInfinity,
+    /// A public key was supplied in a different wire encoding than the
+    /// operation expected (e.g. compressed bytes where uncompressed were
+    /// required).
+    PublicKeyEncodingMismatch { expected: PublicKeyEncoding, actual: PublicKeyEncoding },
+    /// A signature was supplied in a different wire encoding than the
+    /// operation expected (e.g. DER bytes where compact `r || s` was
+    /// required).
+    SignatureEncodingMismatch { expected: SignatureEncoding, actual: SignatureEncoding },
     /// Internal error.
     Internal(String),
+    /// Unsealing rejected a blob whose bound monotonic counter value is
+    /// below the counter's current value - the host fed back a stale copy
+    /// of previously sealed state (a rollback/replay attempt).
+    RollbackDetected { sealed_value: u32, current_value: u32 },
+    /// A remote-attestation quote or certificate chain failed verification
+    /// (malformed encoding, broken signature chain, expired validity
+    /// window, or a mismatched binding between the quote's report data and
+    /// its attestation key).
+    AttestationFailed(String),
+    /// An unseal/reseal operation read a [`SealedDataHeader`] whose format
+    /// version is newer than this enclave build understands - e.g. data
+    /// sealed by a later enclave upgrade fed back to an older build.
+    /// Distinct from [`EnclaveError::UnsealError`] so callers can tell
+    /// "this blob is corrupt" apart from "this blob needs a newer build".
+    UnsupportedSealVersion { found: u32, max_supported: u32 },
 }
 
 impl core::fmt::Display for EnclaveError {
@@ -41,12 +71,38 @@ impl core::fmt::Display for EnclaveError {
                 write!(f, "buffer too small: required {}, provided {}", required, provided)
             }
             EnclaveError::NotSupported => write!(f, "operation not supported"),
+            EnclaveError::RecoveryFailed(msg) => write!(f, "public key recovery failed: {}", msg),
+            EnclaveError::AgreementFailed(msg) => write!(f, "key agreement failed: {}", msg),
+            EnclaveError::PointAtInfinity => write!(f, "computation resulted in the point at infinity"),
+            EnclaveError::PublicKeyEncodingMismatch { expected, actual } => {
+                write!(f, "public key encoding mismatch: expected {:?}, got {:?}", expected, actual)
+            }
+            EnclaveError::SignatureEncodingMismatch { expected, actual } => {
+                write!(f, "signature encoding mismatch: expected {:?}, got {:?}", expected, actual)
+            }
             EnclaveError::Internal(msg) => write!(f, "internal error: {}", msg),
+            EnclaveError::RollbackDetected { sealed_value, current_value } => write!(
+                f,
+                "rollback detected: sealed counter value {} is behind current value {}",
+                sealed_value, current_value
+            ),
+            EnclaveError::AttestationFailed(msg) => write!(f, "attestation verification failed: {}", msg),
+            EnclaveError::UnsupportedSealVersion { found, max_supported } => write!(
+                f,
+                "unsupported sealed-data format version {} (this build supports up to {})",
+                found, max_supported
+            ),
         }
     }
 }
 
 /// Sealed data header for versioning and metadata.
+///
+/// Version 2 extends version 1's plaintext/AAD-length-only header with the
+/// sealing policy and enclave identity a blob was produced under, so a
+/// later (possibly upgraded) enclave can tell whose data it is holding
+/// before it tries to unseal it. See `sealing::reseal_data` for the
+/// MRENCLAVE -> MRSIGNER migration this enables.
 #[repr(C)]
 #[derive(Clone, Copy)]
 pub struct SealedDataHeader {
@@ -60,6 +116,19 @@ pub struct SealedDataHeader {
     pub plaintext_len: u32,
     /// Length of additional authenticated data.
     pub aad_len: u32,
+    /// Sealing policy that produced this blob: `0` = MRENCLAVE, `1` =
+    /// MRSIGNER. See `sealing::SealingPolicy`.
+    pub policy: u8,
+    policy_padding: [u8; 3],
+    /// ISV product id of the enclave that sealed this blob.
+    pub isv_prod_id: u16,
+    isv_prod_id_padding: [u8; 2],
+    /// MRSIGNER of the enclave that sealed this blob.
+    pub mr_signer: [u8; 32],
+    /// Caller-chosen tag identifying which key-derivation context (e.g.
+    /// which logical key or purpose) this blob belongs to, so migration
+    /// code handling multiple kinds of sealed state can tell them apart.
+    pub key_context_id: [u8; 16],
     /// Reserved for future use.
     pub reserved: [u8; 8],
 }
@@ -68,16 +137,30 @@ impl SealedDataHeader {
     /// Magic number: "SEAL"
     pub const MAGIC: [u8; 4] = [0x53, 0x45, 0x41, 0x4C];
     /// Current version.
-    pub const VERSION: u32 = 1;
+    pub const VERSION: u32 = 2;
 
     /// Create a new header.
-    pub fn new(plaintext_len: u32, aad_len: u32) -> Self {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        plaintext_len: u32,
+        aad_len: u32,
+        policy: u8,
+        isv_prod_id: u16,
+        mr_signer: [u8; 32],
+        key_context_id: [u8; 16],
+    ) -> Self {
         Self {
             magic: Self::MAGIC,
             version: Self::VERSION,
             timestamp: 0, // Would be set from OCALL
             plaintext_len,
             aad_len,
+            policy,
+            policy_padding: [0; 3],
+            isv_prod_id,
+            isv_prod_id_padding: [0; 2],
+            mr_signer,
+            key_context_id,
             reserved: [0; 8],
         }
     }
@@ -129,6 +212,10 @@ pub struct KeyMetadata {
     pub created_at: u64,
     /// Whether the key can be exported.
     pub exportable: bool,
+    /// BIP-32 derivation path (e.g. `"m/44'/60'/0'/0/0"`) if this key was
+    /// derived from a sealed master seed via [`crate::bip32`] rather than
+    /// generated and sealed directly. `None` for standalone keys.
+    pub derivation_path: Option<String>,
 }
 
 /// Supported key types.
@@ -138,6 +225,8 @@ pub enum KeyType {
     EcdsaP256,
     /// ECDSA secp256k1 (Bitcoin/Ethereum).
     EcdsaSecp256k1,
+    /// BIP-340 Schnorr over secp256k1, x-only keys (Taproot).
+    SchnorrSecp256k1,
     /// AES-256.
     Aes256,
     /// Ed25519.
@@ -150,6 +239,7 @@ impl KeyType {
         match self {
             KeyType::EcdsaP256 => 32,
             KeyType::EcdsaSecp256k1 => 32,
+            KeyType::SchnorrSecp256k1 => 32,
             KeyType::Aes256 => 32,
             KeyType::Ed25519 => 32,
         }
@@ -160,6 +250,7 @@ impl KeyType {
         match self {
             KeyType::EcdsaP256 => 65,      // Uncompressed: 04 || x || y
             KeyType::EcdsaSecp256k1 => 65, // Uncompressed: 04 || x || y
+            KeyType::SchnorrSecp256k1 => 32, // x-only, even-y representative
             KeyType::Aes256 => 0,          // Symmetric key, no public key
             KeyType::Ed25519 => 32,
         }
@@ -170,10 +261,64 @@ impl KeyType {
         match self {
             KeyType::EcdsaP256 => 64,      // r || s
             KeyType::EcdsaSecp256k1 => 64, // r || s
+            KeyType::SchnorrSecp256k1 => 64, // R.x || s
             KeyType::Aes256 => 0,          // Not a signing key
             KeyType::Ed25519 => 64,
         }
     }
+
+    /// Get the signature size in bytes for a given output encoding.
+    ///
+    /// `SignatureEncoding::Recoverable` only makes sense for `EcdsaSecp256k1`
+    /// today, where it adds the 1-byte recovery id (`v`) to `r || s`.
+    /// `SignatureEncoding::Der` is variable-length, so this returns the
+    /// worst-case bound (72 bytes: a 2-byte `SEQUENCE` header plus two
+    /// 35-byte `INTEGER`s, each a 32-byte value with tag, length, and an
+    /// optional sign-padding byte).
+    pub fn signature_size_for(&self, encoding: SignatureEncoding) -> usize {
+        match encoding {
+            SignatureEncoding::Plain => self.signature_size(),
+            SignatureEncoding::Recoverable => self.signature_size() + 1,
+            SignatureEncoding::Der => 72,
+        }
+    }
+
+    /// Get the public key size in bytes for a given export encoding.
+    pub fn public_key_size_for(&self, encoding: PublicKeyEncoding) -> usize {
+        match encoding {
+            PublicKeyEncoding::Uncompressed => self.public_key_size(),
+            PublicKeyEncoding::Compressed => match self {
+                KeyType::EcdsaP256 | KeyType::EcdsaSecp256k1 => 33,
+                // Already compact, single-form representations.
+                KeyType::SchnorrSecp256k1 | KeyType::Ed25519 => self.public_key_size(),
+                KeyType::Aes256 => 0,
+            },
+        }
+    }
+}
+
+/// Output encoding for a signing operation, chosen per call rather than
+/// baked into the key itself.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SignatureEncoding {
+    /// Plain `r || s`.
+    Plain,
+    /// Compact recoverable form `r || s || v` (secp256k1 only).
+    Recoverable,
+    /// DER-encoded `SEQUENCE { r INTEGER, s INTEGER }`, for interop with
+    /// standard secp256k1/P-256 tooling.
+    Der,
+}
+
+/// Export encoding for an elliptic-curve public key, chosen per call rather
+/// than baked into the key itself.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PublicKeyEncoding {
+    /// Uncompressed SEC1 point: `04 || x || y`.
+    Uncompressed,
+    /// Compressed SEC1 point: `02/03 || x`, where the prefix encodes the
+    /// parity of `y`.
+    Compressed,
 }
 
 /// Script execution request.
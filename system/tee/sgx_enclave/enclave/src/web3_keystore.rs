@@ -0,0 +1,490 @@
+//! Passphrase-protected, portable private-key storage (Web3 Secret Storage
+//! format, the same document shape produced by `geth`/`eth-keyfile`).
+//!
+//! [`crate::keystore`] persists the *whole* key vault sealed to this
+//! specific enclave's MRENCLAVE/MRSIGNER identity - it can't leave the
+//! machine, and `EcdsaKeyPair`/`Secp256k1KeyPair::private_key_bytes` hand
+//! out raw key material with no protection at all once it is outside the
+//! enclave. This module covers the case those two don't: exporting or
+//! importing a *single* key under a human-chosen passphrase, readable by
+//! any standard Ethereum keystore tool regardless of which enclave (or
+//! none at all) produced it.
+//!
+//! The key is wrapped with AES-128-CTR under a key derived from the
+//! passphrase via scrypt or PBKDF2-HMAC-SHA256, and authenticated with a
+//! Keccak-256 MAC over the derived key's second half and the ciphertext -
+//! the same construction `geth` uses, so [`encrypt_keystore`] output can be
+//! opened by any compatible tool and [`decrypt_keystore`] can open keys
+//! exported from one. `sgx_tstd` has no `serde`/`scrypt` crate available
+//! (see the hand-rolled TOML parser in [`crate::policy`] for the same
+//! constraint), so both the KDFs and the JSON document are built and read
+//! by hand here against the fixed schema the format defines.
+
+use std::prelude::v1::*;
+use std::vec::Vec;
+
+use crate::crypto::{aes128_ctr_xor, ct_eq, hmac_sha256, keccak256, random_bytes};
+use crate::types::{EnclaveError, EnclaveResult};
+
+/// Which password-based KDF derives the wrapping key. Both produce a
+/// 32-byte output: the first 16 bytes become the AES-128-CTR key, the
+/// second 16 bytes are folded into the MAC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kdf {
+    Scrypt { n: u32, r: u32, p: u32 },
+    Pbkdf2 { iterations: u32 },
+}
+
+impl Kdf {
+    /// `geth`'s default scrypt parameters (N=2^18, r=8, p=1).
+    pub fn default_scrypt() -> Self {
+        Kdf::Scrypt { n: 1 << 18, r: 8, p: 1 }
+    }
+}
+
+/// Encrypts `private_key` under `passphrase`, returning a Web3 Secret
+/// Storage v3 JSON document.
+pub fn encrypt_keystore(private_key: &[u8], passphrase: &[u8], kdf: Kdf) -> EnclaveResult<String> {
+    let salt = random_bytes(32)?;
+    let dk = derive_key(passphrase, &salt, &kdf)?;
+
+    let mut aes_key = [0u8; 16];
+    aes_key.copy_from_slice(&dk[..16]);
+    let mut iv = [0u8; 16];
+    iv.copy_from_slice(&random_bytes(16)?);
+
+    let ciphertext = aes128_ctr_xor(&aes_key, &iv, private_key);
+    let mac = compute_mac(&dk, &ciphertext);
+
+    let mut id_bytes = [0u8; 16];
+    id_bytes.copy_from_slice(&random_bytes(16)?);
+
+    let kdf_name = match kdf {
+        Kdf::Scrypt { .. } => "scrypt",
+        Kdf::Pbkdf2 { .. } => "pbkdf2",
+    };
+    let kdfparams_json = match kdf {
+        Kdf::Scrypt { n, r, p } => format!(
+            "{{\"dklen\":32,\"n\":{},\"r\":{},\"p\":{},\"salt\":\"{}\"}}",
+            n, r, p, hex_encode(&salt)
+        ),
+        Kdf::Pbkdf2 { iterations } => format!(
+            "{{\"dklen\":32,\"c\":{},\"prf\":\"hmac-sha256\",\"salt\":\"{}\"}}",
+            iterations,
+            hex_encode(&salt)
+        ),
+    };
+
+    Ok(format!(
+        "{{\"version\":3,\"id\":\"{}\",\"crypto\":{{\"cipher\":\"aes-128-ctr\",\
+         \"cipherparams\":{{\"iv\":\"{}\"}},\"ciphertext\":\"{}\",\"kdf\":\"{}\",\
+         \"kdfparams\":{},\"mac\":\"{}\"}}}}",
+        format_uuid(&id_bytes),
+        hex_encode(&iv),
+        hex_encode(&ciphertext),
+        kdf_name,
+        kdfparams_json,
+        hex_encode(&mac),
+    ))
+}
+
+/// Decrypts a document produced by [`encrypt_keystore`] (or any compatible
+/// Web3 Secret Storage v3 keystore) given the original passphrase.
+pub fn decrypt_keystore(document: &str, passphrase: &[u8]) -> EnclaveResult<Vec<u8>> {
+    let version = find_u64_field(document, "version")?;
+    if version != 3 {
+        return Err(EnclaveError::UnsealError(format!("unsupported keystore version: {}", version)));
+    }
+    let crypto_obj = find_object_field(document, "crypto")?;
+
+    let cipher = find_string_field(&crypto_obj, "cipher")?;
+    if cipher != "aes-128-ctr" {
+        return Err(EnclaveError::NotSupported);
+    }
+    let cipherparams = find_object_field(&crypto_obj, "cipherparams")?;
+    let iv = to_array_16(&hex_decode(&find_string_field(&cipherparams, "iv")?)?)?;
+    let ciphertext = hex_decode(&find_string_field(&crypto_obj, "ciphertext")?)?;
+
+    let kdf_name = find_string_field(&crypto_obj, "kdf")?;
+    let kdfparams = find_object_field(&crypto_obj, "kdfparams")?;
+    let salt = hex_decode(&find_string_field(&kdfparams, "salt")?)?;
+    let kdf = match kdf_name.as_str() {
+        "scrypt" => Kdf::Scrypt {
+            n: find_u64_field(&kdfparams, "n")? as u32,
+            r: find_u64_field(&kdfparams, "r")? as u32,
+            p: find_u64_field(&kdfparams, "p")? as u32,
+        },
+        "pbkdf2" => Kdf::Pbkdf2 { iterations: find_u64_field(&kdfparams, "c")? as u32 },
+        other => return Err(EnclaveError::Internal(format!("unsupported kdf: {}", other))),
+    };
+
+    let dk = derive_key(passphrase, &salt, &kdf)?;
+    let expected_mac = hex_decode(&find_string_field(&crypto_obj, "mac")?)?;
+    let computed_mac = compute_mac(&dk, &ciphertext);
+    if !ct_eq(&computed_mac, &expected_mac) {
+        return Err(EnclaveError::UnsealError(
+            "keystore MAC mismatch - wrong passphrase or corrupted file".to_string(),
+        ));
+    }
+
+    let mut aes_key = [0u8; 16];
+    aes_key.copy_from_slice(&dk[..16]);
+    Ok(aes128_ctr_xor(&aes_key, &iv, &ciphertext))
+}
+
+/// `mac = keccak256(dk[16..32] || ciphertext)`, per the Web3 Secret
+/// Storage definition.
+fn compute_mac(dk: &[u8; 32], ciphertext: &[u8]) -> [u8; 32] {
+    let mut mac_input = Vec::with_capacity(16 + ciphertext.len());
+    mac_input.extend_from_slice(&dk[16..32]);
+    mac_input.extend_from_slice(ciphertext);
+    keccak256(&mac_input)
+}
+
+fn derive_key(passphrase: &[u8], salt: &[u8], kdf: &Kdf) -> EnclaveResult<[u8; 32]> {
+    let okm = match *kdf {
+        Kdf::Scrypt { n, r, p } => scrypt(passphrase, salt, n as u64, r as usize, p as usize, 32)?,
+        Kdf::Pbkdf2 { iterations } => pbkdf2_hmac_sha256(passphrase, salt, iterations, 32)?,
+    };
+    let mut dk = [0u8; 32];
+    dk.copy_from_slice(&okm);
+    Ok(dk)
+}
+
+// --- PBKDF2-HMAC-SHA256 (RFC 8018) -------------------------------------
+
+fn pbkdf2_hmac_sha256(password: &[u8], salt: &[u8], iterations: u32, dklen: usize) -> EnclaveResult<Vec<u8>> {
+    const HLEN: usize = 32;
+    if iterations == 0 {
+        return Err(EnclaveError::InvalidParameter);
+    }
+    let block_count = (dklen + HLEN - 1) / HLEN;
+
+    let mut dk = Vec::with_capacity(block_count * HLEN);
+    for block_index in 1..=block_count as u32 {
+        let mut salt_block = salt.to_vec();
+        salt_block.extend_from_slice(&block_index.to_be_bytes());
+
+        let mut u = hmac_sha256(password, &salt_block)?;
+        let mut t = u;
+        for _ in 1..iterations {
+            u = hmac_sha256(password, &u)?;
+            for k in 0..HLEN {
+                t[k] ^= u[k];
+            }
+        }
+        dk.extend_from_slice(&t);
+    }
+    dk.truncate(dklen);
+    Ok(dk)
+}
+
+// --- scrypt (RFC 7914) --------------------------------------------------
+
+/// `scrypt(P, S, N, r, p, dkLen)`: PBKDF2 splits the password into `p`
+/// blocks, each is mixed through [`ro_mix`] (memory-hard Salsa20/8
+/// block-mixing), and a final PBKDF2 pass compresses the mixed blocks to
+/// `dkLen` bytes.
+fn scrypt(password: &[u8], salt: &[u8], n: u64, r: usize, p: usize, dklen: usize) -> EnclaveResult<Vec<u8>> {
+    if n < 2 || !n.is_power_of_two() || r == 0 || p == 0 {
+        return Err(EnclaveError::InvalidParameter);
+    }
+    let block_bytes = 128 * r;
+
+    let mut b = pbkdf2_hmac_sha256(password, salt, 1, p * block_bytes)?;
+    for block in b.chunks_mut(block_bytes) {
+        let mixed = ro_mix(block, r, n);
+        block.copy_from_slice(&mixed);
+    }
+
+    pbkdf2_hmac_sha256(password, &b, 1, dklen)
+}
+
+fn ro_mix(b: &[u8], r: usize, n: u64) -> Vec<u8> {
+    let block_bytes = 128 * r;
+    let n = n as usize;
+
+    let mut v = Vec::with_capacity(n * block_bytes);
+    let mut x = b.to_vec();
+    for _ in 0..n {
+        v.extend_from_slice(&x);
+        x = block_mix(&x, r);
+    }
+
+    for _ in 0..n {
+        let j = integerify(&x, r) as usize % n;
+        let mut t = vec![0u8; block_bytes];
+        for k in 0..block_bytes {
+            t[k] = x[k] ^ v[j * block_bytes + k];
+        }
+        x = block_mix(&t, r);
+    }
+    x
+}
+
+fn integerify(x: &[u8], r: usize) -> u64 {
+    let offset = (2 * r - 1) * 64;
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&x[offset..offset + 8]);
+    u64::from_le_bytes(bytes)
+}
+
+fn block_mix(b_in: &[u8], r: usize) -> Vec<u8> {
+    let block_count = 2 * r;
+    let mut x = [0u8; 64];
+    x.copy_from_slice(&b_in[(block_count - 1) * 64..block_count * 64]);
+
+    let mut y = vec![0u8; block_count * 64];
+    for i in 0..block_count {
+        let mut t = [0u8; 64];
+        for j in 0..64 {
+            t[j] = x[j] ^ b_in[i * 64 + j];
+        }
+        x = salsa20_8(&t);
+        y[i * 64..(i + 1) * 64].copy_from_slice(&x);
+    }
+
+    let mut out = vec![0u8; block_count * 64];
+    for i in 0..r {
+        out[i * 64..(i + 1) * 64].copy_from_slice(&y[(2 * i) * 64..(2 * i + 1) * 64]);
+    }
+    for i in 0..r {
+        out[(r + i) * 64..(r + i + 1) * 64].copy_from_slice(&y[(2 * i + 1) * 64..(2 * i + 2) * 64]);
+    }
+    out
+}
+
+/// The Salsa20/8 core (8 rounds instead of Salsa20's 20) scrypt mixes
+/// each 64-byte block through.
+fn salsa20_8(input: &[u8; 64]) -> [u8; 64] {
+    let mut x = [0u32; 16];
+    for i in 0..16 {
+        x[i] = u32::from_le_bytes([input[4 * i], input[4 * i + 1], input[4 * i + 2], input[4 * i + 3]]);
+    }
+    let mut b = x;
+
+    for _ in 0..4 {
+        quarter_round(&mut b, 4, 0, 12, 7);
+        quarter_round(&mut b, 8, 4, 0, 9);
+        quarter_round(&mut b, 12, 8, 4, 13);
+        quarter_round(&mut b, 0, 12, 8, 18);
+
+        quarter_round(&mut b, 9, 5, 1, 7);
+        quarter_round(&mut b, 13, 9, 5, 9);
+        quarter_round(&mut b, 1, 13, 9, 13);
+        quarter_round(&mut b, 5, 1, 13, 18);
+
+        quarter_round(&mut b, 14, 10, 6, 7);
+        quarter_round(&mut b, 2, 14, 10, 9);
+        quarter_round(&mut b, 6, 2, 14, 13);
+        quarter_round(&mut b, 10, 6, 2, 18);
+
+        quarter_round(&mut b, 3, 15, 11, 7);
+        quarter_round(&mut b, 7, 3, 15, 9);
+        quarter_round(&mut b, 11, 7, 3, 13);
+        quarter_round(&mut b, 15, 11, 7, 18);
+
+        quarter_round(&mut b, 1, 0, 3, 7);
+        quarter_round(&mut b, 2, 1, 0, 9);
+        quarter_round(&mut b, 3, 2, 1, 13);
+        quarter_round(&mut b, 0, 3, 2, 18);
+
+        quarter_round(&mut b, 6, 5, 4, 7);
+        quarter_round(&mut b, 7, 6, 5, 9);
+        quarter_round(&mut b, 4, 7, 6, 13);
+        quarter_round(&mut b, 5, 4, 7, 18);
+
+        quarter_round(&mut b, 11, 10, 9, 7);
+        quarter_round(&mut b, 8, 11, 10, 9);
+        quarter_round(&mut b, 9, 8, 11, 13);
+        quarter_round(&mut b, 10, 9, 8, 18);
+
+        quarter_round(&mut b, 12, 15, 14, 7);
+        quarter_round(&mut b, 13, 12, 15, 9);
+        quarter_round(&mut b, 14, 13, 12, 13);
+        quarter_round(&mut b, 15, 14, 13, 18);
+    }
+
+    let mut out = [0u8; 64];
+    for i in 0..16 {
+        let word = x[i].wrapping_add(b[i]);
+        out[4 * i..4 * i + 4].copy_from_slice(&word.to_le_bytes());
+    }
+    out
+}
+
+/// `b[dst] ^= (b[src1] + b[src2]).rotate_left(rot)`.
+fn quarter_round(b: &mut [u32; 16], dst: usize, src1: usize, src2: usize, rot: u32) {
+    b[dst] ^= b[src1].wrapping_add(b[src2]).rotate_left(rot);
+}
+
+// --- Minimal JSON field access ------------------------------------------
+//
+// There's no `serde_json` available to `sgx_tstd`, and the keystore
+// document's schema is fixed, so rather than a general-purpose parser this
+// only knows how to pull a named field's raw value out of an object's text
+// (mirroring the scope of the hand-rolled TOML subset in `crate::policy`).
+
+fn locate_value_start(text: &str, key: &str) -> EnclaveResult<usize> {
+    let needle = format!("\"{}\"", key);
+    let key_pos = text
+        .find(&needle)
+        .ok_or_else(|| EnclaveError::Internal(format!("keystore document is missing field: {}", key)))?;
+    let after_key = &text[key_pos + needle.len()..];
+    let colon_offset = after_key
+        .find(':')
+        .ok_or_else(|| EnclaveError::Internal(format!("malformed field: {}", key)))?;
+
+    let mut idx = key_pos + needle.len() + colon_offset + 1;
+    let bytes = text.as_bytes();
+    while idx < bytes.len() && (bytes[idx] as char).is_whitespace() {
+        idx += 1;
+    }
+    Ok(idx)
+}
+
+fn find_string_field(text: &str, key: &str) -> EnclaveResult<String> {
+    let start = locate_value_start(text, key)?;
+    let rest = &text[start..];
+    if !rest.starts_with('"') {
+        return Err(EnclaveError::Internal(format!("expected a string for field: {}", key)));
+    }
+    let end = rest[1..]
+        .find('"')
+        .ok_or_else(|| EnclaveError::Internal(format!("unterminated string for field: {}", key)))?;
+    Ok(rest[1..1 + end].to_string())
+}
+
+fn find_u64_field(text: &str, key: &str) -> EnclaveResult<u64> {
+    let start = locate_value_start(text, key)?;
+    let rest = &text[start..];
+    let end = rest.find(|c: char| c == ',' || c == '}').unwrap_or(rest.len());
+    rest[..end]
+        .trim()
+        .parse::<u64>()
+        .map_err(|_| EnclaveError::Internal(format!("expected a number for field: {}", key)))
+}
+
+fn find_object_field(text: &str, key: &str) -> EnclaveResult<String> {
+    let start = locate_value_start(text, key)?;
+    let rest = &text[start..];
+    if !rest.starts_with('{') {
+        return Err(EnclaveError::Internal(format!("expected an object for field: {}", key)));
+    }
+
+    let mut depth = 0usize;
+    for (i, c) in rest.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok(rest[..=i].to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    Err(EnclaveError::Internal(format!("unterminated object for field: {}", key)))
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() * 2);
+    for b in bytes {
+        out.push_str(&format!("{:02x}", b));
+    }
+    out
+}
+
+fn hex_decode(s: &str) -> EnclaveResult<Vec<u8>> {
+    if s.len() % 2 != 0 {
+        return Err(EnclaveError::InvalidParameter);
+    }
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(s.len() / 2);
+    for chunk in bytes.chunks(2) {
+        let hi = (chunk[0] as char).to_digit(16).ok_or(EnclaveError::InvalidParameter)?;
+        let lo = (chunk[1] as char).to_digit(16).ok_or(EnclaveError::InvalidParameter)?;
+        out.push(((hi << 4) | lo) as u8);
+    }
+    Ok(out)
+}
+
+/// Formats 16 random bytes as a textual UUID, forcing the version (4) and
+/// variant (RFC 4122) bits the way `geth` does for a keystore's `id` field.
+fn format_uuid(bytes: &[u8; 16]) -> String {
+    let mut b = *bytes;
+    b[6] = (b[6] & 0x0f) | 0x40;
+    b[8] = (b[8] & 0x3f) | 0x80;
+    format!(
+        "{:02x}{:02x}{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}",
+        b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7], b[8], b[9], b[10], b[11], b[12], b[13], b[14], b[15]
+    )
+}
+
+fn to_array_16(bytes: &[u8]) -> EnclaveResult<[u8; 16]> {
+    if bytes.len() != 16 {
+        return Err(EnclaveError::InvalidParameter);
+    }
+    let mut out = [0u8; 16];
+    out.copy_from_slice(bytes);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pbkdf2_hmac_sha256_rfc6070_like_vector() {
+        // RFC 6070 is defined over HMAC-SHA1; this is the widely-used
+        // HMAC-SHA256 analogue ("password"/"salt", c=1, dklen=32).
+        let dk = pbkdf2_hmac_sha256(b"password", b"salt", 1, 32).unwrap();
+        assert_eq!(
+            hex_encode(&dk),
+            "120fb6cffcf8b32c43e7225256c4f837a86548c92ccc35480805987cb70be17b"
+        );
+    }
+
+    #[test]
+    fn test_scrypt_rfc7914_empty_vector() {
+        // RFC 7914 section 12, test vector 1.
+        let dk = scrypt(b"", b"", 16, 1, 1, 64).unwrap();
+        assert_eq!(
+            hex_encode(&dk),
+            "77d6576238657b203b19ca42c18a0497f16b4844e3074ae8dfdffa3fede21442fcd0069ded0948f8326a753a0fc81f17e8d3e0fb2e0d3628cf35e20c38d18906"
+        );
+    }
+
+    #[test]
+    fn test_keystore_roundtrip_scrypt() {
+        let private_key = [0x42u8; 32];
+        let passphrase = b"correct horse battery staple";
+
+        let document =
+            encrypt_keystore(&private_key, passphrase, Kdf::Scrypt { n: 1024, r: 8, p: 1 }).unwrap();
+        let recovered = decrypt_keystore(&document, passphrase).unwrap();
+
+        assert_eq!(recovered, private_key.to_vec());
+    }
+
+    #[test]
+    fn test_keystore_roundtrip_pbkdf2() {
+        let private_key = [0x7au8; 32];
+        let passphrase = b"another passphrase";
+
+        let document = encrypt_keystore(&private_key, passphrase, Kdf::Pbkdf2 { iterations: 2048 }).unwrap();
+        let recovered = decrypt_keystore(&document, passphrase).unwrap();
+
+        assert_eq!(recovered, private_key.to_vec());
+    }
+
+    #[test]
+    fn test_keystore_rejects_wrong_passphrase() {
+        let private_key = [0x11u8; 32];
+        let document =
+            encrypt_keystore(&private_key, b"correct passphrase", Kdf::Scrypt { n: 1024, r: 8, p: 1 }).unwrap();
+
+        assert!(decrypt_keystore(&document, b"wrong passphrase").is_err());
+    }
+}